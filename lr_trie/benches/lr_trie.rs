@@ -0,0 +1,121 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+use criterion::{
+    black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput,
+};
+use lr_trie::LeftRightTrie;
+use patriecia::{MockTreeStore, Sha256, VersionedTrie};
+
+type Trie<'a> = LeftRightTrie<'a, String, u64, MockTreeStore, Sha256>;
+
+fn new_trie() -> Trie<'static> {
+    LeftRightTrie::new(Arc::new(MockTreeStore::new(true)))
+}
+
+fn entries(count: u64) -> Vec<(String, Option<u64>)> {
+    (0..count).map(|n| (format!("key-{n}"), Some(n))).collect()
+}
+
+fn populated_trie(count: u64) -> Trie<'static> {
+    let mut trie = new_trie();
+    trie.extend(entries(count)).unwrap();
+    trie
+}
+
+fn insert(c: &mut Criterion) {
+    let mut group = c.benchmark_group("insert");
+    group.throughput(Throughput::Elements(100));
+    group.bench_function("100 single inserts", |b| {
+        b.iter_batched(
+            new_trie,
+            |mut trie| {
+                for n in 0..100 {
+                    trie.insert(format!("key-{n}"), n).unwrap();
+                }
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+fn extend(c: &mut Criterion) {
+    let mut group = c.benchmark_group("extend");
+    for size in [100, 1_000, 10_000] {
+        group.throughput(Throughput::Elements(size));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, &size| {
+            b.iter_batched(
+                || (new_trie(), entries(size)),
+                |(mut trie, entries)| trie.extend(entries).unwrap(),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn proofs(c: &mut Criterion) {
+    let trie = populated_trie(1_000);
+    let key = "key-500".to_string();
+    let handle = trie.handle();
+    let root = handle.root_hash(1).unwrap();
+    let bundle = trie.get_proof_bundle(&key, 1).unwrap();
+
+    let mut group = c.benchmark_group("proof");
+    group.bench_function("generate", |b| {
+        b.iter(|| trie.get_proof_bundle(black_box(&key), 1).unwrap())
+    });
+    group.bench_function("verify", |b| {
+        b.iter_batched(
+            || bundle.proof::<Sha256>().unwrap(),
+            |proof| {
+                handle
+                    .verify_proof::<String>(bundle.key_hash(), 1, root, proof)
+                    .unwrap()
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+fn publish_under_reader_load(c: &mut Criterion) {
+    let mut trie = new_trie();
+    let stop = Arc::new(AtomicBool::new(false));
+    let readers = (0..4)
+        .map(|_| {
+            let factory = trie.read_handle.factory();
+            let stop = stop.clone();
+            thread::spawn(move || {
+                let handle = factory.handle();
+                while !stop.load(Ordering::Relaxed) {
+                    if let Some(tree) = handle.enter() {
+                        black_box(tree.version());
+                    }
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut n = 0;
+    c.bench_function("publish under reader load", |b| {
+        b.iter(|| {
+            trie.insert(format!("key-{n}"), n).unwrap();
+            n += 1;
+        })
+    });
+
+    stop.store(true, Ordering::Relaxed);
+    for reader in readers {
+        reader.join().unwrap();
+    }
+}
+
+criterion_group!(benches, insert, extend, proofs, publish_under_reader_load);
+criterion_main!(benches);
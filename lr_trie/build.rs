@@ -0,0 +1,28 @@
+fn main() {
+    #[cfg(feature = "proto")]
+    proto::generate().expect("failed to generate the protobuf types");
+}
+
+#[cfg(feature = "proto")]
+mod proto {
+    use std::{env, error::Error, path::PathBuf};
+
+    /// Generates `lr_trie::proto` from `proto/lr_trie.proto`.
+    pub fn generate() -> Result<(), Box<dyn Error>> {
+        // This script builds both lr_trie and the workspace root package, whose manifests
+        // sit at different depths.
+        let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR")?);
+        let proto_dir = ["proto", "lr_trie/proto"]
+            .iter()
+            .map(|dir| manifest_dir.join(dir))
+            .find(|dir| dir.is_dir())
+            .ok_or("proto directory not found")?;
+        println!("cargo:rerun-if-changed={}", proto_dir.display());
+
+        // protox parses the .proto in Rust, so building doesn't need protoc installed.
+        let descriptors = protox::compile(["lr_trie.proto"], [&proto_dir])?;
+        prost_build::Config::new().compile_fds(descriptors)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,13 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use lr_trie::ProofBundle;
+use patriecia::Sha256;
+
+// Proof bundles arrive from the network, so neither decoding the envelope nor
+// decoding the proof it carries may panic on arbitrary bytes.
+fuzz_target!(|data: &[u8]| {
+    if let Ok(bundle) = ProofBundle::from_bytes(data) {
+        let _ = bundle.proof::<Sha256>();
+    }
+});
@@ -0,0 +1,39 @@
+#![no_main]
+
+use std::sync::Arc;
+
+use libfuzzer_sys::fuzz_target;
+use lr_trie::{LeftRightTrie, StateChunk};
+use patriecia::{MockTreeStore, Sha256};
+
+type Trie<'a> = LeftRightTrie<'a, Vec<u8>, Vec<u8>, MockTreeStore, Sha256>;
+
+fn new_trie() -> Trie<'static> {
+    LeftRightTrie::new(Arc::new(MockTreeStore::new(true)))
+}
+
+// State chunks are received from syncing peers. Importing them must either rebuild the
+// exact state they claim to come from or be rejected without writing anything.
+fuzz_target!(|data: &[u8]| {
+    let Ok(chunks) = bincode::deserialize::<Vec<StateChunk>>(data) else {
+        return;
+    };
+
+    let mut trie = new_trie();
+    let Ok(root) = trie.import_chunks(chunks) else {
+        assert_eq!(trie.version(), Ok(0));
+        return;
+    };
+    assert_eq!(trie.root_latest(), Ok(root));
+    if trie.is_empty().unwrap() {
+        return;
+    }
+
+    // Whatever was accepted must survive another round through StateChunkIter.
+    let exported = trie
+        .export_chunks(trie.version().unwrap(), 7)
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(new_trie().import_chunks(exported), Ok(root));
+});
@@ -1,4 +1,4 @@
-use crate::Operation;
+use crate::{span::span, Operation};
 use left_right::Absorb;
 pub use left_right::ReadHandleFactory;
 use patriecia::{
@@ -15,18 +15,25 @@ where
     H: SimpleHasher,
 {
     fn absorb_first(&mut self, operation: &mut Operation, _other: &Self) {
-        // TODO: the unwrap_or_default avoids panic but is not logically sound and should
-        // not be used in production since `Version` is monotonically increasing and could
-        // eventually overflow.
-        //
-        // cc @nopestack
-        let increment_version =
-            |vers: &mut u64| vers.checked_add(INCREMENT_ARG).unwrap_or_default();
+        let vers = operation.version();
+        span!(
+            "absorb",
+            version = vers,
+            batch_size = operation.batch_size()
+        );
+        // `LeftRightTrie` refuses to append operations past its max version, so this
+        // only guards against operations appended directly to the write handle.
+        // Skipping is preferable to wrapping, which would silently reset the tree.
+        let Some(next_version) = vers.checked_add(INCREMENT_ARG) else {
+            error!("operation at version {vers} would overflow the tree version, skipping");
+            return;
+        };
         match operation {
             // TODO: report errors via instrumentation
-            Operation::Add(key_val, vers) => {
-                match self.put_value_set(vec![key_val.to_owned()], increment_version(vers)) {
+            Operation::Add(key_val, _) => {
+                match self.put_value_set(vec![key_val.to_owned()], next_version) {
                     Ok((_, batch)) => {
+                        span!("write_node_batch", version = next_version);
                         if let Err(err) = self.reader().write_node_batch(&batch.node_batch) {
                             error!("Operation::Add failed to write changes to database: {err}")
                         }
@@ -34,9 +41,10 @@ where
                     Err(err) => error!("Operation::Add failed to insert key: {err}"),
                 }
             }
-            Operation::Remove(key, vers) => {
-                match self.put_value_set(vec![(*key, None)], increment_version(vers)) {
+            Operation::Remove(key, _) => {
+                match self.put_value_set(vec![(*key, None)], next_version) {
                     Ok((_, batch)) => {
+                        span!("write_node_batch", version = next_version);
                         if let Err(err) = self.reader().write_node_batch(&batch.node_batch) {
                             error!("Operation::Remove failed to write changes to database: {err}")
                         }
@@ -44,16 +52,15 @@ where
                     Err(err) => error!("Operation::Remove failed to remove value for key: {err}"),
                 }
             }
-            Operation::Extend(kvs, vers) => {
-                match self.put_value_set(kvs.to_vec(), increment_version(vers)) {
-                    Ok((_, batch)) => {
-                        if let Err(err) = self.reader().write_node_batch(&batch.node_batch) {
-                            error!("Operation::Extend failed to write changes to database: {err}")
-                        }
+            Operation::Extend(kvs, _) => match self.put_value_set(kvs.to_vec(), next_version) {
+                Ok((_, batch)) => {
+                    span!("write_node_batch", version = next_version);
+                    if let Err(err) = self.reader().write_node_batch(&batch.node_batch) {
+                        error!("Operation::Extend failed to write changes to database: {err}")
                     }
-                    Err(err) => error!("Operation::Extend failed batch update: {err}"),
                 }
-            }
+                Err(err) => error!("Operation::Extend failed batch update: {err}"),
+            },
         }
     }
 
@@ -0,0 +1,237 @@
+use std::{fmt::Debug, marker::PhantomData};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{LeftRightTrieError, Result};
+
+/// Serialization format used to turn keys and values into the bytes stored in the trie.
+///
+/// Tries and wrappers are parameterized over a `Codec` so that the wire format
+/// of a table can be chosen independently of the rest of the storage stack.
+pub trait Codec: Debug + Clone + Send + Sync {
+    /// Serialize a value into bytes.
+    fn encode<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>>;
+
+    /// Serialize a key into the bytes its key hash is computed from.
+    ///
+    /// Defaults to [`Codec::encode`]. Codecs that add framing to stored values should
+    /// leave keys alone, so that switching codecs doesn't change where keys are stored.
+    fn encode_key<T: Serialize + ?Sized>(key: &T) -> Result<Vec<u8>> {
+        Self::encode(key)
+    }
+
+    /// Deserialize a value from bytes previously produced by [`Codec::encode`].
+    fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T>;
+}
+
+/// The default [`Codec`], backed by `bincode`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BincodeCodec;
+
+impl Codec for BincodeCodec {
+    fn encode<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>> {
+        bincode::serialize(value)
+            .map_err(|err| LeftRightTrieError::SerializationError(err.to_string()))
+    }
+
+    fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+        bincode::deserialize(bytes).map_err(|err| LeftRightTrieError::Codec(err.to_string()))
+    }
+}
+
+/// A [`Codec`] backed by MessagePack via `rmp-serde`.
+#[cfg(feature = "rmp")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RmpCodec;
+
+#[cfg(feature = "rmp")]
+impl Codec for RmpCodec {
+    fn encode<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>> {
+        rmp_serde::to_vec(value)
+            .map_err(|err| LeftRightTrieError::SerializationError(err.to_string()))
+    }
+
+    fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+        rmp_serde::from_slice(bytes).map_err(|err| LeftRightTrieError::Codec(err.to_string()))
+    }
+}
+
+/// First byte of every value written by [`EnvelopeCodec`].
+pub const ENVELOPE_MAGIC: u8 = 0xe7;
+
+/// The envelope layout written by [`EnvelopeCodec`]: the magic byte, this version, the
+/// payload length as a little-endian `u32` and then the payload encoded by the inner codec.
+pub const ENVELOPE_VERSION: u8 = 1;
+
+/// A [`Codec`] that wraps the values produced by `C` in a small versioned envelope, so
+/// the stored format can evolve without breaking existing databases.
+///
+/// Keys are encoded by `C` unchanged, so a table can switch to this codec without moving
+/// its keys. Values without a well-formed envelope are decoded with `C` directly, which
+/// lets values written before the switch be read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnvelopeCodec<C = BincodeCodec>(PhantomData<C>);
+
+impl<C: Codec> EnvelopeCodec<C> {
+    /// Returns the envelope version of `bytes`, or `None` if they were not written by an
+    /// `EnvelopeCodec`.
+    pub fn envelope_version(bytes: &[u8]) -> Option<u8> {
+        split_envelope(bytes).map(|(version, _)| version)
+    }
+}
+
+impl<C: Codec> Codec for EnvelopeCodec<C> {
+    fn encode<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>> {
+        let payload = C::encode(value)?;
+        let len = u32::try_from(payload.len()).map_err(|_| {
+            LeftRightTrieError::SerializationError("value is too large for an envelope".into())
+        })?;
+
+        let mut bytes = vec![ENVELOPE_MAGIC, ENVELOPE_VERSION];
+        bytes.extend(len.to_le_bytes());
+        bytes.extend(payload);
+
+        Ok(bytes)
+    }
+
+    fn encode_key<T: Serialize + ?Sized>(key: &T) -> Result<Vec<u8>> {
+        C::encode_key(key)
+    }
+
+    fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+        match split_envelope(bytes) {
+            // A legacy value could still look like an envelope by chance, in which case
+            // its payload most likely fails to decode.
+            Some((ENVELOPE_VERSION, payload)) => {
+                C::decode(payload).or_else(|err| C::decode(bytes).map_err(|_| err))
+            }
+            Some((version, _)) => C::decode(bytes).map_err(|_| {
+                LeftRightTrieError::Codec(format!("unsupported envelope version {version}"))
+            }),
+            None => C::decode(bytes),
+        }
+    }
+}
+
+/// Splits `bytes` into the envelope version and payload if they have an envelope header
+/// whose length matches the rest of the buffer.
+fn split_envelope(bytes: &[u8]) -> Option<(u8, &[u8])> {
+    match bytes {
+        [ENVELOPE_MAGIC, version, l0, l1, l2, l3, payload @ ..]
+            if u32::from_le_bytes([*l0, *l1, *l2, *l3]) as usize == payload.len() =>
+        {
+            Some((*version, payload))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    type Envelope = EnvelopeCodec<BincodeCodec>;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Sample {
+        name: String,
+        id: u64,
+        tags: Vec<String>,
+        parent: Option<Box<Sample>>,
+    }
+
+    fn sample() -> Sample {
+        Sample {
+            name: "child".to_string(),
+            id: 7,
+            tags: vec!["a".to_string(), "b".to_string()],
+            parent: Some(Box::new(Sample {
+                name: "parent".to_string(),
+                id: u64::MAX,
+                tags: vec![],
+                parent: None,
+            })),
+        }
+    }
+
+    fn should_roundtrip<C: Codec>() {
+        let bytes = C::encode(&sample()).unwrap();
+        assert_eq!(C::decode::<Sample>(&bytes).unwrap(), sample());
+        assert_eq!(C::encode_key("key").unwrap(), C::encode("key").unwrap());
+        assert!(matches!(
+            C::decode::<Sample>(&bytes[..bytes.len() / 2]),
+            Err(LeftRightTrieError::Codec(_))
+        ));
+    }
+
+    #[test]
+    fn bincode_should_roundtrip() {
+        should_roundtrip::<BincodeCodec>();
+    }
+
+    #[cfg(feature = "rmp")]
+    #[test]
+    fn rmp_should_roundtrip() {
+        should_roundtrip::<RmpCodec>();
+    }
+
+    #[cfg(feature = "rmp")]
+    #[test]
+    fn rmp_and_bincode_should_not_share_a_format() {
+        assert_ne!(
+            RmpCodec::encode(&sample()).unwrap(),
+            BincodeCodec::encode(&sample()).unwrap()
+        );
+    }
+
+    #[test]
+    fn envelope_should_roundtrip() {
+        let bytes = Envelope::encode(&("key", 42u64)).unwrap();
+        assert_eq!(Envelope::envelope_version(&bytes), Some(ENVELOPE_VERSION));
+        assert_eq!(
+            Envelope::decode::<(String, u64)>(&bytes).unwrap(),
+            ("key".to_string(), 42)
+        );
+    }
+
+    #[test]
+    fn envelope_should_decode_legacy_values() {
+        let legacy = BincodeCodec::encode(&("key", 42u64)).unwrap();
+        assert_eq!(Envelope::envelope_version(&legacy), None);
+        assert_eq!(
+            Envelope::decode::<(String, u64)>(&legacy).unwrap(),
+            ("key".to_string(), 42)
+        );
+    }
+
+    #[test]
+    fn envelope_should_not_mistake_legacy_values_for_envelopes() {
+        // Encodes to e7 01 00 00 00 00 00 00, which starts like an envelope header.
+        let legacy = BincodeCodec::encode(&0x01e7u64).unwrap();
+        assert_eq!(&legacy[..2], &[ENVELOPE_MAGIC, ENVELOPE_VERSION]);
+        assert_eq!(Envelope::envelope_version(&legacy), None);
+        assert_eq!(Envelope::decode::<u64>(&legacy), Ok(0x01e7));
+    }
+
+    #[test]
+    fn envelope_should_leave_keys_alone() {
+        assert_eq!(
+            Envelope::encode_key("key").unwrap(),
+            BincodeCodec::encode("key").unwrap()
+        );
+    }
+
+    #[test]
+    fn envelope_should_reject_unknown_versions() {
+        let bytes = [ENVELOPE_MAGIC, ENVELOPE_VERSION + 1, 3, 0, 0, 0, 1, 2, 3];
+        assert_eq!(
+            Envelope::decode::<(String, u64)>(&bytes),
+            Err(LeftRightTrieError::Codec(format!(
+                "unsupported envelope version {}",
+                ENVELOPE_VERSION + 1
+            )))
+        );
+    }
+}
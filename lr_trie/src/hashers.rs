@@ -0,0 +1,143 @@
+//! Ready-made [`SimpleHasher`](patriecia::SimpleHasher) implementations besides
+//! patriecia's `Sha256`, each behind its own cargo feature.
+
+#[cfg(feature = "blake3")]
+pub use self::blake3_hasher::Blake3Hasher;
+#[cfg(feature = "keccak")]
+pub use self::keccak_hasher::Keccak256Hasher;
+
+#[cfg(feature = "blake3")]
+mod blake3_hasher {
+    use patriecia::SimpleHasher;
+
+    use crate::NamedHasher;
+
+    /// BLAKE3 with a 32 byte output.
+    #[derive(Debug, Default, Clone)]
+    pub struct Blake3Hasher(blake3::Hasher);
+
+    impl SimpleHasher for Blake3Hasher {
+        fn new() -> Self {
+            Self(blake3::Hasher::new())
+        }
+
+        fn update(&mut self, data: &[u8]) {
+            self.0.update(data);
+        }
+
+        fn finalize(self) -> [u8; 32] {
+            *self.0.finalize().as_bytes()
+        }
+    }
+
+    impl NamedHasher for Blake3Hasher {
+        const NAME: &'static str = "blake3";
+    }
+}
+
+#[cfg(feature = "keccak")]
+mod keccak_hasher {
+    use std::fmt::{self, Debug, Formatter};
+
+    use patriecia::SimpleHasher;
+    use tiny_keccak::{Hasher, Keccak};
+
+    use crate::NamedHasher;
+
+    /// Keccak-256 as used by Ethereum, not the finalized SHA3-256.
+    #[derive(Clone)]
+    pub struct Keccak256Hasher(Keccak);
+
+    impl SimpleHasher for Keccak256Hasher {
+        fn new() -> Self {
+            Self(Keccak::v256())
+        }
+
+        fn update(&mut self, data: &[u8]) {
+            self.0.update(data);
+        }
+
+        fn finalize(self) -> [u8; 32] {
+            let mut output = [0u8; 32];
+            self.0.finalize(&mut output);
+            output
+        }
+    }
+
+    impl NamedHasher for Keccak256Hasher {
+        const NAME: &'static str = "keccak256";
+    }
+
+    impl Default for Keccak256Hasher {
+        fn default() -> Self {
+            <Self as SimpleHasher>::new()
+        }
+    }
+
+    impl Debug for Keccak256Hasher {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            f.write_str("Keccak256Hasher")
+        }
+    }
+}
+
+#[cfg(all(test, any(feature = "blake3", feature = "keccak")))]
+mod tests {
+    use std::sync::Arc;
+
+    use patriecia::{MockTreeStore, SimpleHasher};
+
+    use super::*;
+    use crate::LeftRightTrie;
+
+    fn digest<H: SimpleHasher>(data: &[u8]) -> String {
+        let mut hasher = H::new();
+        hasher.update(data);
+        hex::encode(hasher.finalize())
+    }
+
+    /// Hex-encoded root of a trie holding `key-0..key-15`, so that a change to the
+    /// hasher, the codec or the tree layout shows up as a different root.
+    fn root<H: SimpleHasher>() -> String {
+        let mut trie = LeftRightTrie::<String, u64, _, H>::new(Arc::new(MockTreeStore::new(true)));
+        trie.extend((0..16).map(|n| (format!("key-{n}"), Some(n))).collect())
+            .unwrap();
+        hex::encode(trie.root_latest().unwrap().0)
+    }
+
+    #[cfg(feature = "blake3")]
+    #[test]
+    fn blake3_matches_reference_digest() {
+        assert_eq!(
+            digest::<Blake3Hasher>(b""),
+            "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+        );
+    }
+
+    #[cfg(feature = "blake3")]
+    #[test]
+    fn blake3_root_hash_is_stable() {
+        assert_eq!(
+            root::<Blake3Hasher>(),
+            "ba9e915d39ce95b25cd01212394d0c08c2d33146918397e159318aa1fba51208"
+        );
+    }
+
+    #[cfg(feature = "keccak")]
+    #[test]
+    fn keccak256_matches_reference_digest() {
+        assert_eq!(
+            digest::<Keccak256Hasher>(b""),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+    }
+
+    #[cfg(feature = "keccak")]
+    #[test]
+    fn keccak256_root_hash_is_stable() {
+        assert_eq!(
+            root::<Keccak256Hasher>(),
+            "40b4e832669e1b9abddabcffe995bed7d175784646e2528f2df1d77242131f51"
+        );
+    }
+}
@@ -0,0 +1,78 @@
+use patriecia::{KeyHash, RootHash};
+
+use crate::{LeftRightTrieError, Result};
+
+/// Canonical `0x`-prefixed hex encoding for 32 byte hashes, so identifiers can be
+/// round-tripped through RPC and logs.
+pub trait HexHash: Sized {
+    /// Encode as `0x` followed by 64 lowercase hex digits.
+    fn to_hex(&self) -> String;
+
+    /// Parse the output of [`HexHash::to_hex`]. The `0x` prefix is required and the
+    /// digits must be lowercase, so every hash has exactly one accepted encoding.
+    fn from_hex(hex: &str) -> Result<Self>;
+}
+
+impl HexHash for KeyHash {
+    fn to_hex(&self) -> String {
+        encode(&self.0)
+    }
+
+    fn from_hex(hex: &str) -> Result<Self> {
+        decode(hex).map(KeyHash)
+    }
+}
+
+impl HexHash for RootHash {
+    fn to_hex(&self) -> String {
+        encode(&self.0)
+    }
+
+    fn from_hex(hex: &str) -> Result<Self> {
+        decode(hex).map(RootHash)
+    }
+}
+
+fn encode(bytes: &[u8; 32]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+fn decode(value: &str) -> Result<[u8; 32]> {
+    let digits = value
+        .strip_prefix("0x")
+        .ok_or_else(|| LeftRightTrieError::Codec(format!("{value:?} is missing the 0x prefix")))?;
+    if digits.bytes().any(|digit| digit.is_ascii_uppercase()) {
+        return Err(LeftRightTrieError::Codec(format!(
+            "{value:?} is not lowercase hex"
+        )));
+    }
+    let mut bytes = [0u8; 32];
+    hex::decode_to_slice(digits, &mut bytes)
+        .map_err(|err| LeftRightTrieError::Codec(format!("invalid hash {value:?}: {err}")))?;
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_roundtrip_hashes() {
+        let key = KeyHash([0xab; 32]);
+        let encoded = key.to_hex();
+        assert_eq!(encoded, format!("0x{}", "ab".repeat(32)));
+        assert_eq!(KeyHash::from_hex(&encoded), Ok(key));
+        assert_eq!(RootHash::from_hex(&encoded), Ok(RootHash([0xab; 32])));
+        assert!(RootHash::from_hex(&encoded.to_uppercase().replace("0X", "0x")).is_err());
+        assert!(RootHash::from_hex(&encoded.replacen("ab", "aB", 1)).is_err());
+    }
+
+    #[test]
+    fn should_reject_malformed_hashes() {
+        let digits = "ab".repeat(32);
+        assert!(KeyHash::from_hex(&digits).is_err());
+        assert!(KeyHash::from_hex(&format!("0x{}", &digits[2..])).is_err());
+        assert!(KeyHash::from_hex(&format!("0x{}zz", &digits[2..])).is_err());
+    }
+}
@@ -4,9 +4,30 @@
 pub use patriecia::H256;
 
 mod absorb_op;
+mod codec;
+mod hashers;
+mod hex_hash;
+mod metrics;
 pub mod op;
+mod pinned;
+mod proof;
+#[cfg(test)]
+mod proptests;
+#[cfg(feature = "proto")]
+pub mod proto;
 mod result;
+mod sharded;
+mod span;
+mod speculative;
+mod sync;
 mod tree_wrapper;
 mod trie;
+mod typed_handle;
+pub mod verify;
+mod writer_view;
 
-pub use crate::{absorb_op::*, op::*, result::*, tree_wrapper::*, trie::*};
+pub use crate::{
+    absorb_op::*, codec::*, hashers::*, hex_hash::*, metrics::*, op::*, pinned::*, proof::*,
+    result::*, sharded::*, speculative::*, sync::*, tree_wrapper::*, trie::*, typed_handle::*,
+    writer_view::*,
+};
@@ -0,0 +1,28 @@
+use std::{fmt::Debug, time::Duration};
+
+/// Sink for storage layer measurements.
+///
+/// Implementors forward the values to whatever backend the operator uses
+/// (e.g. a Prometheus exporter). Every method has a no-op default so sinks
+/// only need to implement what they care about.
+pub trait StorageMetrics: Debug + Send + Sync {
+    /// Called for every read served by a [`JellyfishMerkleTreeWrapper`] the sink is
+    /// attached to, e.g. a `get`, `contains`, proof or iteration.
+    ///
+    /// [`JellyfishMerkleTreeWrapper`]: crate::JellyfishMerkleTreeWrapper
+    fn record_read(&self) {}
+
+    /// Called each time operations are appended to the write handle, with the
+    /// number of key-value pairs carried by the operation.
+    fn record_write(&self, _batch_size: usize) {}
+
+    /// Called after every `publish` with the time spent waiting for readers
+    /// to move to the other copy of the data.
+    fn record_publish(&self, _elapsed: Duration) {}
+}
+
+/// [`StorageMetrics`] implementation that discards everything.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopMetrics;
+
+impl StorageMetrics for NoopMetrics {}
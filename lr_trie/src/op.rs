@@ -13,3 +13,22 @@ pub enum Operation {
     /// Batch update at a specified version
     Extend(Vec<(KeyHash, Option<OwnedValue>)>, Version),
 }
+
+impl Operation {
+    /// The version the operation was appended at. Absorbing it produces the next version.
+    pub fn version(&self) -> Version {
+        match self {
+            Operation::Add(_, vers) | Operation::Remove(_, vers) | Operation::Extend(_, vers) => {
+                *vers
+            }
+        }
+    }
+
+    /// The number of key-value pairs the operation writes.
+    pub fn batch_size(&self) -> usize {
+        match self {
+            Operation::Add(..) | Operation::Remove(..) => 1,
+            Operation::Extend(kvs, _) => kvs.len(),
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use patriecia::{
+    RootHash, SimpleHasher, SparseMerkleProof, TreeReader, TreeWriter, Version, VersionedDatabase,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{BincodeCodec, Codec, JellyfishMerkleTreeWrapper, Result};
+
+/// A read handle fixed to a single `Version`, so that several reads observe the same
+/// state even if a publish lands between them.
+///
+/// Created with [`JellyfishMerkleTreeWrapper::pin`] or `TypedTrieHandle::pin`.
+#[derive(Debug, Clone)]
+pub struct PinnedView<D, H, C = BincodeCodec>
+where
+    D: TreeReader + TreeWriter + VersionedDatabase,
+    H: SimpleHasher,
+    C: Codec,
+{
+    inner: JellyfishMerkleTreeWrapper<D, H, C>,
+    version: Version,
+}
+
+impl<D, H, C> PinnedView<D, H, C>
+where
+    D: TreeReader + TreeWriter + VersionedDatabase,
+    H: SimpleHasher,
+    C: Codec,
+{
+    pub(crate) fn new(inner: JellyfishMerkleTreeWrapper<D, H, C>, version: Version) -> Self {
+        Self { inner, version }
+    }
+
+    /// The version every read through this view is made at.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Get the value associated with a key, or `None` if it has no value at the pinned version.
+    pub fn get<K, V>(&self, key: &K) -> Result<Option<V>>
+    where
+        K: for<'b> Deserialize<'b> + Serialize + Clone,
+        V: for<'b> Deserialize<'b> + Serialize + Clone,
+    {
+        self.inner.get_opt(key, self.version)
+    }
+
+    /// Get the values for several keys, in the order given.
+    pub fn batch_get<K, V>(&self, keys: &[K]) -> Result<Vec<Option<V>>>
+    where
+        K: for<'b> Deserialize<'b> + Serialize + Clone,
+        V: for<'b> Deserialize<'b> + Serialize + Clone,
+    {
+        keys.iter().map(|key| self.get(key)).collect()
+    }
+
+    /// Returns true if the tree contains the specified key at the pinned version.
+    pub fn contains<'b, K>(&self, key: &'b K) -> Result<bool>
+    where
+        K: Serialize + Deserialize<'b>,
+    {
+        self.inner.contains(key, self.version)
+    }
+
+    /// Returns whether the tree contains each of `keys` at the pinned version.
+    pub fn batch_contains<K>(&self, keys: &[K]) -> Result<Vec<bool>>
+    where
+        K: Serialize + for<'b> Deserialize<'b>,
+    {
+        keys.iter().map(|key| self.contains(key)).collect()
+    }
+
+    /// Get the `RootHash` of the tree at the pinned version.
+    pub fn root_hash(&self) -> Result<RootHash> {
+        self.inner.root_hash(self.version)
+    }
+
+    /// Creates a Merkle proof for a key at the pinned version.
+    pub fn get_proof<'b, K>(&mut self, key: &K) -> Result<SparseMerkleProof<H>>
+    where
+        K: Serialize + Deserialize<'b>,
+    {
+        self.inner.get_proof(key, self.version)
+    }
+}
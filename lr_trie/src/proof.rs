@@ -0,0 +1,79 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use patriecia::{KeyHash, RootHash, Sha256, SimpleHasher, SparseMerkleProof, Version};
+use serde::{Deserialize, Serialize};
+
+use crate::{LeftRightTrieError, Proof, Result};
+
+/// A [`SimpleHasher`] with a stable identifier, used to tag proofs with the
+/// hash function they have to be verified with.
+pub trait NamedHasher: SimpleHasher {
+    const NAME: &'static str;
+}
+
+impl NamedHasher for Sha256 {
+    const NAME: &'static str = "sha256";
+}
+
+/// Self-describing, wire-friendly envelope around a [`SparseMerkleProof`].
+///
+/// Carries everything a light client needs to check the proof against a root
+/// hash without access to the database it was generated from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct ProofBundle {
+    pub key_hash: [u8; 32],
+    pub version: Version,
+    pub root_hash: [u8; 32],
+    pub proof: Proof,
+    pub hasher: String,
+}
+
+impl ProofBundle {
+    pub fn new<H: NamedHasher>(
+        key_hash: KeyHash,
+        version: Version,
+        root_hash: RootHash,
+        proof: &SparseMerkleProof<H>,
+    ) -> Result<Self> {
+        let proof = bincode::serialize(proof)
+            .map_err(|err| LeftRightTrieError::SerializationError(err.to_string()))?;
+
+        Ok(Self {
+            key_hash: key_hash.0,
+            version,
+            root_hash: root_hash.0,
+            proof,
+            hasher: H::NAME.to_string(),
+        })
+    }
+
+    pub fn key_hash(&self) -> KeyHash {
+        KeyHash(self.key_hash)
+    }
+
+    pub fn root_hash(&self) -> RootHash {
+        RootHash(self.root_hash)
+    }
+
+    /// Decode the wrapped proof, failing if it was produced with a different hasher than `H`.
+    pub fn proof<H: NamedHasher>(&self) -> Result<SparseMerkleProof<H>> {
+        if self.hasher != H::NAME {
+            return Err(LeftRightTrieError::Proof(format!(
+                "proof was produced with {}, not {}",
+                self.hasher,
+                H::NAME
+            )));
+        }
+
+        bincode::deserialize(&self.proof).map_err(|err| LeftRightTrieError::Codec(err.to_string()))
+    }
+
+    /// Encode the bundle with borsh for transport.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        borsh::to_vec(self).map_err(|err| LeftRightTrieError::SerializationError(err.to_string()))
+    }
+
+    /// Decode a bundle previously produced by [`ProofBundle::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        borsh::from_slice(bytes).map_err(|err| LeftRightTrieError::Codec(err.to_string()))
+    }
+}
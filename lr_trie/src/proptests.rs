@@ -0,0 +1,142 @@
+//! Property-based tests checking `LeftRightTrie` against a `HashMap` model.
+
+use std::{collections::HashMap, sync::Arc};
+
+use patriecia::{MockTreeStore, Sha256, Version};
+use proptest::{collection, option, prelude::*};
+
+use crate::LeftRightTrie;
+
+type Trie<'a> = LeftRightTrie<'a, u8, u64, MockTreeStore, Sha256>;
+
+/// Keys are drawn from a small domain so that operations collide often.
+const KEYS: u8 = 16;
+
+#[derive(Debug, Clone)]
+enum Op {
+    Insert(u8, u64),
+    InsertDeferred(u8, u64),
+    Remove(u8),
+    Extend(Vec<(u8, Option<u64>)>),
+    Publish,
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        (0..KEYS, any::<u64>()).prop_map(|(key, value)| Op::Insert(key, value)),
+        (0..KEYS, any::<u64>()).prop_map(|(key, value)| Op::InsertDeferred(key, value)),
+        (0..KEYS).prop_map(Op::Remove),
+        collection::btree_map(0..KEYS, option::of(any::<u64>()), 1..8)
+            .prop_map(|values| Op::Extend(values.into_iter().collect())),
+        Just(Op::Publish),
+    ]
+}
+
+fn new_trie() -> Trie<'static> {
+    LeftRightTrie::new(Arc::new(MockTreeStore::new(true)))
+}
+
+/// The expected contents of the trie, plus the versions it should have published.
+#[derive(Debug, Default)]
+struct Model {
+    values: HashMap<u8, u64>,
+    version: Version,
+    pending: Version,
+}
+
+impl Model {
+    /// Every operation becomes its own version once published, including removals of
+    /// keys the trie doesn't contain.
+    fn publish(&mut self) {
+        self.version += self.pending;
+        self.pending = 0;
+    }
+}
+
+/// Applies `op` to both the trie and the model.
+fn apply(trie: &mut Trie, model: &mut Model, op: Op) {
+    match op {
+        Op::Insert(key, value) => {
+            trie.insert(key, value).unwrap();
+            model.values.insert(key, value);
+            model.pending += 1;
+            model.publish();
+        }
+        Op::InsertDeferred(key, value) => {
+            trie.insert_deferred(key, value).unwrap();
+            model.values.insert(key, value);
+            model.pending += 1;
+        }
+        Op::Remove(key) => {
+            trie.remove(key).unwrap();
+            model.values.remove(&key);
+            model.pending += 1;
+            model.publish();
+        }
+        Op::Extend(values) => {
+            for (key, value) in &values {
+                match value {
+                    Some(value) => model.values.insert(*key, *value),
+                    None => model.values.remove(key),
+                };
+            }
+            trie.extend(values).unwrap();
+            model.pending += 1;
+            model.publish();
+        }
+        Op::Publish => {
+            trie.publish();
+            model.publish();
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn trie_matches_model(ops in collection::vec(op(), 1..32)) {
+        let mut trie = new_trie();
+        let mut model = Model::default();
+
+        for op in ops {
+            apply(&mut trie, &mut model, op);
+
+            prop_assert_eq!(trie.version().unwrap(), model.version);
+
+            let view = trie.writer_view();
+            for key in 0..KEYS {
+                prop_assert_eq!(view.get(&key).unwrap(), model.values.get(&key).copied());
+            }
+        }
+
+        trie.publish();
+        model.publish();
+        prop_assert_eq!(trie.version().unwrap(), model.version);
+        let model = model.values;
+        trie.publish();
+        prop_assert_eq!(trie.len().unwrap(), model.len());
+        if model.is_empty() {
+            return Ok(());
+        }
+
+        // The root hash only depends on the contents of the tree, not on how it got there.
+        let mut reference = new_trie();
+        reference
+            .extend(model.iter().map(|(key, value)| (*key, Some(*value))).collect())
+            .unwrap();
+        let root = trie.root_latest().unwrap();
+        prop_assert_eq!(root, reference.root_latest().unwrap());
+
+        let version = trie.version().unwrap();
+        let handle = trie.handle();
+        for key in model.keys() {
+            let bundle = trie.get_proof_bundle(key, version).unwrap();
+            prop_assert_eq!(bundle.root_hash(), root);
+            let proof = bundle.proof::<Sha256>().unwrap();
+            handle
+                .verify_proof::<u8>(bundle.key_hash(), version, root, proof)
+                .unwrap();
+        }
+    }
+}
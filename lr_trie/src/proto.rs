@@ -0,0 +1,208 @@
+//! Protobuf types for [`ProofBundle`](crate::ProofBundle) and
+//! [`StateChunk`](crate::StateChunk), generated from `proto/lr_trie.proto`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{LeftRightTrieError, Result};
+
+include!(concat!(env!("OUT_DIR"), "/lr_trie.rs"));
+
+/// The serde layout of patriecia's `SparseMerkleProof`, whose fields are private, so the
+/// bincode-encoded proof in a [`crate::ProofBundle`] can be taken apart and rebuilt
+/// without knowing its hasher.
+#[derive(Serialize, Deserialize)]
+struct RawProof {
+    leaf: Option<([u8; 32], [u8; 32])>,
+    siblings: Vec<[u8; 32]>,
+}
+
+impl TryFrom<crate::ProofBundle> for ProofBundle {
+    type Error = LeftRightTrieError;
+
+    fn try_from(bundle: crate::ProofBundle) -> Result<Self> {
+        let proof: RawProof = bincode::deserialize(&bundle.proof)
+            .map_err(|err| LeftRightTrieError::Codec(err.to_string()))?;
+
+        Ok(Self {
+            key_hash: bundle.key_hash.to_vec(),
+            version: bundle.version,
+            root_hash: bundle.root_hash.to_vec(),
+            proof: Some(SparseMerkleProof {
+                leaf: proof.leaf.map(|(key_hash, value_hash)| SparseMerkleLeaf {
+                    key_hash: key_hash.to_vec(),
+                    value_hash: value_hash.to_vec(),
+                }),
+                siblings: proof
+                    .siblings
+                    .iter()
+                    .map(|sibling| sibling.to_vec())
+                    .collect(),
+            }),
+            hasher: bundle.hasher,
+        })
+    }
+}
+
+impl TryFrom<ProofBundle> for crate::ProofBundle {
+    type Error = LeftRightTrieError;
+
+    fn try_from(bundle: ProofBundle) -> Result<Self> {
+        let proof = bundle
+            .proof
+            .ok_or_else(|| LeftRightTrieError::Codec("proof is missing".to_string()))?;
+        let leaf = proof
+            .leaf
+            .map(|leaf| -> Result<_> {
+                Ok((
+                    hash(leaf.key_hash, "leaf.key_hash")?,
+                    hash(leaf.value_hash, "leaf.value_hash")?,
+                ))
+            })
+            .transpose()?;
+        let siblings = proof
+            .siblings
+            .into_iter()
+            .map(|sibling| hash(sibling, "siblings"))
+            .collect::<Result<Vec<_>>>()?;
+        let proof = bincode::serialize(&RawProof { leaf, siblings })
+            .map_err(|err| LeftRightTrieError::SerializationError(err.to_string()))?;
+
+        Ok(Self {
+            key_hash: hash(bundle.key_hash, "key_hash")?,
+            version: bundle.version,
+            root_hash: hash(bundle.root_hash, "root_hash")?,
+            proof,
+            hasher: bundle.hasher,
+        })
+    }
+}
+
+impl From<crate::StateChunk> for StateChunk {
+    fn from(chunk: crate::StateChunk) -> Self {
+        Self {
+            version: chunk.version,
+            root_hash: chunk.root_hash.to_vec(),
+            index: chunk.index,
+            entries: chunk
+                .entries
+                .into_iter()
+                .map(|(key_hash, value)| StateEntry {
+                    key_hash: key_hash.to_vec(),
+                    value,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<StateChunk> for crate::StateChunk {
+    type Error = LeftRightTrieError;
+
+    fn try_from(chunk: StateChunk) -> Result<Self> {
+        let entries = chunk
+            .entries
+            .into_iter()
+            .map(|entry| -> Result<_> { Ok((hash(entry.key_hash, "key_hash")?, entry.value)) })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            version: chunk.version,
+            root_hash: hash(chunk.root_hash, "root_hash")?,
+            index: chunk.index,
+            entries,
+        })
+    }
+}
+
+fn hash(bytes: Vec<u8>, field: &str) -> Result<[u8; 32]> {
+    bytes.try_into().map_err(|bytes: Vec<u8>| {
+        LeftRightTrieError::Codec(format!("{field} must be 32 bytes, got {}", bytes.len()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use patriecia::{MockTreeStore, Sha256};
+    use prost::Message;
+
+    use super::*;
+    use crate::{verify, BincodeCodec, Codec, LeftRightTrie};
+
+    fn trie() -> LeftRightTrie<'static, String, u64, MockTreeStore, Sha256> {
+        let mut trie = LeftRightTrie::new(Arc::new(MockTreeStore::new(true)));
+        trie.extend((0..8).map(|n| (format!("key-{n}"), Some(n))).collect())
+            .unwrap();
+        trie
+    }
+
+    fn roundtrip(bundle: crate::ProofBundle) -> crate::ProofBundle {
+        let bytes = ProofBundle::try_from(bundle).unwrap().encode_to_vec();
+        crate::ProofBundle::try_from(ProofBundle::decode(bytes.as_slice()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn inclusion_proofs_should_roundtrip_through_protobuf() {
+        let bundle = trie().get_proof_bundle(&"key-3".to_string(), 1).unwrap();
+
+        let message = ProofBundle::try_from(bundle.clone()).unwrap();
+        let proof = message.proof.unwrap();
+        assert_eq!(proof.leaf.unwrap().key_hash, bundle.key_hash.to_vec());
+        assert!(!proof.siblings.is_empty());
+
+        let decoded = roundtrip(bundle.clone());
+        assert_eq!(decoded, bundle);
+        verify::verify_inclusion::<Sha256>(&decoded, &BincodeCodec::encode(&3u64).unwrap())
+            .unwrap();
+    }
+
+    #[test]
+    fn exclusion_proofs_should_roundtrip_through_protobuf() {
+        let bundle = trie().get_proof_bundle(&"missing".to_string(), 1).unwrap();
+
+        let decoded = roundtrip(bundle.clone());
+        assert_eq!(decoded, bundle);
+        verify::verify_exclusion::<Sha256>(&decoded).unwrap();
+    }
+
+    #[test]
+    fn state_chunk_should_roundtrip_through_protobuf() {
+        let chunk = crate::StateChunk {
+            version: 3,
+            root_hash: [9; 32],
+            index: 1,
+            entries: vec![([1; 32], vec![1]), ([2; 32], vec![2, 2])],
+        };
+
+        let bytes = StateChunk::from(chunk.clone()).encode_to_vec();
+        let decoded = StateChunk::decode(bytes.as_slice()).unwrap();
+        assert_eq!(crate::StateChunk::try_from(decoded), Ok(chunk));
+    }
+
+    #[test]
+    fn should_reject_truncated_hashes_and_missing_proofs() {
+        let chunk = StateChunk {
+            root_hash: vec![0; 31],
+            ..Default::default()
+        };
+        assert!(matches!(
+            crate::StateChunk::try_from(chunk),
+            Err(LeftRightTrieError::Codec(_))
+        ));
+
+        let bundle = trie().get_proof_bundle(&"key-3".to_string(), 1).unwrap();
+        let mut message = ProofBundle::try_from(bundle).unwrap();
+        message.proof.as_mut().unwrap().siblings[0].pop();
+        assert!(matches!(
+            crate::ProofBundle::try_from(message.clone()),
+            Err(LeftRightTrieError::Codec(_))
+        ));
+
+        message.proof = None;
+        assert!(matches!(
+            crate::ProofBundle::try_from(message),
+            Err(LeftRightTrieError::Codec(_))
+        ));
+    }
+}
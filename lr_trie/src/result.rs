@@ -1,12 +1,16 @@
-use patriecia::error::TrieError;
+use patriecia::{error::TrieError, RootHash, Version};
 
 pub type Result<T> = std::result::Result<T, LeftRightTrieError>;
 
 #[derive(Debug, Clone, thiserror::Error, PartialEq, Eq)]
 pub enum LeftRightTrieError {
     #[error("failed to deserialize value")]
+    #[deprecated]
     FailedToDeserializeValue,
 
+    #[error("failed to serialize: {0}")]
+    SerializationError(String),
+
     #[error("value not found for key {0:?}")]
     #[deprecated]
     NoValueForKey(Vec<u8>),
@@ -17,6 +21,53 @@ pub enum LeftRightTrieError {
     #[error("trie error: {0}")]
     FailedToGetValueForKey(TrieError),
 
+    #[error("version limit {0} reached")]
+    VersionLimitReached(Version),
+
+    #[error("version {requested} is newer than the latest version {latest}")]
+    VersionTooNew { requested: Version, latest: Version },
+
+    /// Writes were prepared against a different version than the one the trie is at.
+    #[error("expected version {expected}, but the trie is at version {actual}")]
+    VersionConflict { expected: Version, actual: Version },
+
+    /// A rebuilt or combined root hash does not match the one it was checked against.
+    #[error("root hash {actual:?} does not match the expected root hash {expected:?}")]
+    RootMismatch {
+        expected: RootHash,
+        actual: RootHash,
+    },
+
+    #[error("chunk size must be greater than zero")]
+    InvalidChunkSize,
+
+    #[error("a sharded trie needs at least one shard")]
+    NoShards,
+
+    /// State chunks were missing, out of order, or did not come from a single export.
+    #[error("invalid state chunks: {0}")]
+    InvalidChunks(String),
+
+    /// The operation requires a trie without any values.
+    #[error("the trie is not empty")]
+    NotEmpty,
+
+    /// The tree or its backing database failed to read or write nodes.
+    #[error("database error: {0}")]
+    Io(String),
+
+    /// Stored or received bytes could not be decoded.
+    #[error("failed to decode: {0}")]
+    Codec(String),
+
+    /// The write handle was dropped, so readers can no longer observe the trie.
+    #[error("the trie's write handle has been dropped")]
+    Poisoned,
+
+    /// A proof could not be generated, decoded, or did not verify.
+    #[error("proof error: {0}")]
+    Proof(String),
+
     #[error("{0}")]
     Other(String),
 }
@@ -0,0 +1,307 @@
+use std::{
+    fmt::{self, Debug, Formatter},
+    panic,
+    sync::Arc,
+    thread,
+};
+
+use patriecia::{KeyHash, RootHash, SimpleHasher, TreeReader, TreeWriter, VersionedDatabase};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{BincodeCodec, Codec, LeftRightTrie, LeftRightTrieError, Result};
+
+/// Partitions the key space over several [`LeftRightTrie`]s, each with its own
+/// left-right pair and database, so writes to different shards don't serialize
+/// through a single write handle.
+///
+/// Keys are routed by their key hash, and the shards are combined into a single root
+/// by hashing their root hashes in shard order.
+pub struct ShardedTrie<'a, K, V, D, H, C = BincodeCodec>
+where
+    D: TreeReader + TreeWriter + VersionedDatabase,
+    H: SimpleHasher,
+    K: Serialize + Deserialize<'a>,
+    V: Serialize + Deserialize<'a>,
+    C: Codec,
+{
+    shards: Vec<LeftRightTrie<'a, K, V, D, H, C>>,
+}
+
+impl<'a, D, K, V, H, C> ShardedTrie<'a, K, V, D, H, C>
+where
+    D: TreeReader + TreeWriter + VersionedDatabase,
+    H: SimpleHasher,
+    K: Serialize + Deserialize<'a>,
+    V: Serialize + Deserialize<'a>,
+    C: Codec,
+{
+    /// Create a sharded trie with one shard per database. Fails if `dbs` is empty.
+    pub fn new(dbs: Vec<Arc<D>>) -> Result<Self> {
+        if dbs.is_empty() {
+            return Err(LeftRightTrieError::NoShards);
+        }
+
+        Ok(Self {
+            shards: dbs.into_iter().map(LeftRightTrie::new).collect(),
+        })
+    }
+
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    pub fn shards(&self) -> &[LeftRightTrie<'a, K, V, D, H, C>] {
+        &self.shards
+    }
+
+    /// Mutable access to the shards, e.g. to drive each one from its own writer thread.
+    ///
+    /// Writes must go to the shard returned by [`ShardedTrie::shard_for`], otherwise
+    /// the key will not be found on reads.
+    pub fn shards_mut(&mut self) -> &mut [LeftRightTrie<'a, K, V, D, H, C>] {
+        &mut self.shards
+    }
+
+    /// Returns the index of the shard that owns `key`.
+    pub fn shard_for(&self, key: &K) -> Result<usize> {
+        Ok(self.shard_for_hash(&LeftRightTrie::<'a, K, V, D, H, C>::hash_key(key)?))
+    }
+
+    fn shard_for_hash(&self, key: &KeyHash) -> usize {
+        let mut prefix = [0u8; 8];
+        prefix.copy_from_slice(&key.0[..8]);
+
+        (u64::from_be_bytes(prefix) % self.shards.len() as u64) as usize
+    }
+
+    /// Add and publish a key-value pair on the shard that owns the key.
+    pub fn insert(&mut self, key: K, value: V) -> Result<()> {
+        let shard = self.shard_for(&key)?;
+        self.shards[shard].insert(key, value)
+    }
+
+    /// Remove and publish the value stored for a key on the shard that owns it.
+    pub fn remove(&mut self, key: K) -> Result<()> {
+        let shard = self.shard_for(&key)?;
+        self.shards[shard].remove(key)
+    }
+
+    /// Publish the pending operations of every shard.
+    pub fn publish(&mut self) {
+        self.shards.iter_mut().for_each(LeftRightTrie::publish);
+    }
+
+    /// Returns the number of values stored across all shards.
+    pub fn len(&self) -> Result<usize> {
+        self.shards.iter().map(LeftRightTrie::len).sum()
+    }
+
+    /// Returns true if no shard holds any values.
+    pub fn is_empty(&self) -> Result<bool> {
+        for shard in &self.shards {
+            if !shard.is_empty()? {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// The latest root hash of each shard, in shard order.
+    ///
+    /// Shards that have never been written to report [`EMPTY_ROOT_HASH`](crate::EMPTY_ROOT_HASH).
+    pub fn shard_roots(&self) -> Result<Vec<RootHash>> {
+        self.shards.iter().map(LeftRightTrie::root_latest).collect()
+    }
+
+    /// The combined root: the hash of every shard's latest root hash, in shard order.
+    pub fn root_latest(&self) -> Result<RootHash> {
+        let mut hasher = H::new();
+        for root in self.shard_roots()? {
+            hasher.update(&root.0);
+        }
+
+        Ok(RootHash(hasher.finalize()))
+    }
+
+    /// Add and publish a set of key-value pairs, writing to each affected shard from its
+    /// own thread.
+    ///
+    /// Every key and value is encoded and every affected shard is checked for room for
+    /// another version before any shard is written, so the whole batch is rejected if
+    /// either fails.
+    pub fn extend(&mut self, values: Vec<(K, Option<V>)>) -> Result<()>
+    where
+        LeftRightTrie<'a, K, V, D, H, C>: Send,
+    {
+        let mut partitions = (0..self.shards.len())
+            .map(|_| Vec::new())
+            .collect::<Vec<_>>();
+        for (key, value) in values {
+            let (key, value) = LeftRightTrie::<'a, K, V, D, H, C>::encode_entry(key, value)?;
+            partitions[self.shard_for_hash(&key)].push((key, value));
+        }
+        for (shard, partition) in self.shards.iter().zip(&partitions) {
+            if !partition.is_empty() {
+                shard.next_version()?;
+            }
+        }
+
+        thread::scope(|scope| {
+            let writers = self
+                .shards
+                .iter_mut()
+                .zip(partitions)
+                .filter(|(_, partition)| !partition.is_empty())
+                .map(|(shard, partition)| scope.spawn(move || shard.extend_encoded(partition)))
+                .collect::<Vec<_>>();
+
+            writers
+                .into_iter()
+                .map(|writer| {
+                    writer
+                        .join()
+                        .unwrap_or_else(|payload| panic::resume_unwind(payload))
+                })
+                .collect()
+        })
+    }
+}
+
+impl<'a, D, K, V, H, C> ShardedTrie<'a, K, V, D, H, C>
+where
+    D: TreeReader + TreeWriter + VersionedDatabase,
+    H: SimpleHasher,
+    K: Serialize + DeserializeOwned + Clone,
+    V: Serialize + DeserializeOwned + Clone,
+    C: Codec,
+{
+    /// Get the latest published value for a key from the shard that owns it.
+    pub fn get(&self, key: &K) -> Result<Option<V>> {
+        let handle = self.shards[self.shard_for(key)?].try_handle()?;
+        match handle.version() {
+            0 => Ok(None),
+            version => handle.get_opt(key, version),
+        }
+    }
+
+    /// Returns true if the shard that owns `key` holds a published value for it.
+    pub fn contains(&self, key: &K) -> Result<bool> {
+        let handle = self.shards[self.shard_for(key)?].try_handle()?;
+        match handle.version() {
+            0 => Ok(false),
+            version => handle.contains(key, version),
+        }
+    }
+}
+
+impl<'a, D, K, V, H, C> Debug for ShardedTrie<'a, K, V, D, H, C>
+where
+    D: TreeReader + TreeWriter + VersionedDatabase,
+    H: SimpleHasher,
+    K: Serialize + Deserialize<'a>,
+    V: Serialize + Deserialize<'a>,
+    C: Codec,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShardedTrie")
+            .field("shards", &self.shards.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use patriecia::{MockTreeStore, Sha256};
+
+    use super::*;
+    use crate::EMPTY_ROOT_HASH;
+
+    fn sharded(count: usize) -> ShardedTrie<'static, String, u64, MockTreeStore, Sha256> {
+        ShardedTrie::new(
+            (0..count)
+                .map(|_| Arc::new(MockTreeStore::new(true)))
+                .collect(),
+        )
+        .unwrap()
+    }
+
+    fn entries() -> Vec<(String, Option<u64>)> {
+        (0..64).map(|n| (format!("key-{n}"), Some(n))).collect()
+    }
+
+    #[test]
+    fn should_reject_zero_shards() {
+        assert_eq!(
+            ShardedTrie::<String, u64, MockTreeStore, Sha256>::new(vec![]).unwrap_err(),
+            LeftRightTrieError::NoShards
+        );
+    }
+
+    #[test]
+    fn emptied_shards_should_have_the_same_root_as_fresh_ones() {
+        let mut trie = sharded(4);
+        let owner = trie.shard_for(&"a".to_string()).unwrap();
+        trie.insert("a".to_string(), 1).unwrap();
+        assert_ne!(trie.shards()[owner].root_latest(), Ok(EMPTY_ROOT_HASH));
+
+        trie.remove("a".to_string()).unwrap();
+        assert_eq!(trie.shards()[owner].version(), Ok(2));
+        assert_eq!(trie.shards()[owner].root_latest(), Ok(EMPTY_ROOT_HASH));
+        assert_eq!(
+            trie.root_latest().unwrap(),
+            sharded(4).root_latest().unwrap()
+        );
+    }
+
+    #[test]
+    fn extend_should_not_write_any_shard_if_one_rejects_the_batch() {
+        let mut trie = sharded(4);
+        let full = trie.shard_for(&"key-0".to_string()).unwrap();
+        trie.shards_mut()[full].set_max_version(0);
+
+        assert_eq!(
+            trie.extend(entries()),
+            Err(LeftRightTrieError::VersionLimitReached(0))
+        );
+        assert!(trie.shards().iter().all(|shard| shard.version() == Ok(0)));
+    }
+
+    #[test]
+    fn should_route_reads_to_the_owning_shard() {
+        let mut trie = sharded(4);
+        trie.extend(entries()).unwrap();
+        trie.insert("extra".to_string(), 100).unwrap();
+
+        assert_eq!(trie.len().unwrap(), 65);
+        assert!(trie.shards().iter().all(|shard| !shard.is_empty().unwrap()));
+        for n in 0..64 {
+            assert_eq!(trie.get(&format!("key-{n}")).unwrap(), Some(n));
+        }
+        assert_eq!(trie.get(&"extra".to_string()).unwrap(), Some(100));
+
+        trie.remove("extra".to_string()).unwrap();
+        assert!(!trie.contains(&"extra".to_string()).unwrap());
+        assert_eq!(trie.get(&"missing".to_string()).unwrap(), None);
+    }
+
+    #[test]
+    fn combined_root_should_not_depend_on_write_order() {
+        let mut batched = sharded(4);
+        batched.extend(entries()).unwrap();
+
+        let mut single = sharded(4);
+        for (key, value) in entries().into_iter().rev() {
+            single.insert(key, value.unwrap()).unwrap();
+        }
+
+        assert_eq!(
+            batched.root_latest().unwrap(),
+            single.root_latest().unwrap()
+        );
+        assert_ne!(
+            batched.root_latest().unwrap(),
+            sharded(4).root_latest().unwrap()
+        );
+    }
+}
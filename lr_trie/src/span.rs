@@ -0,0 +1,14 @@
+//! Tracing spans around the expensive parts of the storage stack, compiled in only with
+//! the `tracing-spans` feature so the hot paths pay nothing by default.
+
+/// Enters a `debug` span until the end of the enclosing block. Expands to nothing
+/// unless the `tracing-spans` feature is enabled, in which case field expressions are
+/// not evaluated either.
+macro_rules! span {
+    ($name:literal $(, $($fields:tt)*)?) => {
+        #[cfg(feature = "tracing-spans")]
+        let _span = tracing::debug_span!($name $(, $($fields)*)?).entered();
+    };
+}
+
+pub(crate) use span;
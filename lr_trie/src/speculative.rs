@@ -0,0 +1,136 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+
+use patriecia::{
+    JellyfishMerkleTree, KeyHash, OwnedValue, RootHash, SimpleHasher, TreeReader, TreeWriter,
+    Version, VersionedDatabase, VersionedTrie,
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{BincodeCodec, Codec, LeftRightTrieError, Result, EMPTY_ROOT_HASH};
+
+/// Copy-on-write overlay of uncommitted writes on top of a snapshot of a tree.
+///
+/// Reads consult the overlay before falling back to the snapshot, and the root hash
+/// the tree would have with the overlay applied can be computed without writing
+/// anything to the database. The overlay is either merged into a `LeftRightTrie`
+/// with `LeftRightTrie::merge` or discarded by dropping the store.
+pub struct SpeculativeStore<K, V, D, H, C = BincodeCodec>
+where
+    D: TreeReader + TreeWriter + VersionedDatabase,
+    H: SimpleHasher,
+    C: Codec,
+{
+    base: JellyfishMerkleTree<D, H>,
+    base_version: Version,
+    overlay: BTreeMap<KeyHash, Option<OwnedValue>>,
+    _marker: PhantomData<(K, V, C)>,
+}
+
+impl<K, V, D, H, C> SpeculativeStore<K, V, D, H, C>
+where
+    D: TreeReader + TreeWriter + VersionedDatabase,
+    H: SimpleHasher,
+    K: Serialize,
+    V: Serialize + DeserializeOwned,
+    C: Codec,
+{
+    /// Start a speculative overlay on top of `base` at its latest version.
+    pub fn new(base: JellyfishMerkleTree<D, H>) -> Self {
+        let base_version = base.version();
+        Self {
+            base,
+            base_version,
+            overlay: BTreeMap::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// The version of the snapshot the overlay was started from.
+    pub fn base_version(&self) -> Version {
+        self.base_version
+    }
+
+    /// Stage a key-value pair in the overlay.
+    pub fn insert(&mut self, key: K, value: V) -> Result<()> {
+        let key = KeyHash::with::<H>(C::encode_key(&key)?);
+        self.overlay.insert(key, Some(C::encode(&value)?));
+
+        Ok(())
+    }
+
+    /// Stage the removal of a key in the overlay.
+    pub fn remove(&mut self, key: K) -> Result<()> {
+        let key = KeyHash::with::<H>(C::encode_key(&key)?);
+        self.overlay.insert(key, None);
+
+        Ok(())
+    }
+
+    /// Get the value for a key as it would be with the overlay applied.
+    pub fn get(&self, key: &K) -> Result<Option<V>> {
+        let key = KeyHash::with::<H>(C::encode_key(key)?);
+        let raw_value = match self.overlay.get(&key) {
+            Some(staged) => staged.clone(),
+            // Nothing has been written to the snapshot yet, so it has no root to read from.
+            None if self.base_version == 0 => None,
+            None => self
+                .base
+                .get(key, self.base_version)
+                .map_err(|err| LeftRightTrieError::Io(err.to_string()))?,
+        };
+
+        raw_value.map(|raw_value| C::decode(&raw_value)).transpose()
+    }
+
+    /// Compute the root hash the tree would have if the overlay were applied on top of
+    /// the snapshot. Nothing is written to the database.
+    pub fn root_hash(&self) -> Result<RootHash> {
+        if self.overlay.is_empty() {
+            if self.base_version == 0 {
+                return Ok(EMPTY_ROOT_HASH);
+            }
+            return self
+                .base
+                .get_root_hash(self.base_version)
+                .map_err(|err| LeftRightTrieError::Io(err.to_string()));
+        }
+
+        let (root_hash, _) = self
+            .base
+            .put_value_set(self.value_set(), self.base_version + 1)
+            .map_err(|err| LeftRightTrieError::Io(err.to_string()))?;
+
+        Ok(root_hash)
+    }
+
+    /// Returns true if nothing has been staged.
+    pub fn is_empty(&self) -> bool {
+        self.overlay.is_empty()
+    }
+
+    /// The staged writes as a value set, in key hash order.
+    pub fn value_set(&self) -> Vec<(KeyHash, Option<OwnedValue>)> {
+        self.overlay
+            .iter()
+            .map(|(key, value)| (*key, value.clone()))
+            .collect()
+    }
+}
+
+impl<K, V, D, H, C> Debug for SpeculativeStore<K, V, D, H, C>
+where
+    D: TreeReader + TreeWriter + VersionedDatabase,
+    H: SimpleHasher,
+    C: Codec,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpeculativeStore")
+            .field("base_version", &self.base_version)
+            .field("staged", &self.overlay.len())
+            .finish()
+    }
+}
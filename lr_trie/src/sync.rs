@@ -0,0 +1,90 @@
+use std::fmt::Display;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use patriecia::{KeyHash, OwnedValue, RootHash, Version};
+use serde::{Deserialize, Serialize};
+
+use crate::{LeftRightTrieError, Result};
+
+/// A contiguous run of the key-value pairs stored in a tree at a given `Version`.
+///
+/// Chunks are produced in key order by `export_chunks` and carry the root hash of the
+/// exported tree so that the importing side can check it rebuilt the same state.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct StateChunk {
+    pub version: Version,
+    pub root_hash: [u8; 32],
+    pub index: u64,
+    pub entries: Vec<([u8; 32], OwnedValue)>,
+}
+
+impl StateChunk {
+    pub fn root_hash(&self) -> RootHash {
+        RootHash(self.root_hash)
+    }
+
+    /// Converts the chunk into a value set that can be applied to a tree.
+    pub fn into_value_set(self) -> Vec<(KeyHash, Option<OwnedValue>)> {
+        self.entries
+            .into_iter()
+            .map(|(key, value)| (KeyHash(key), Some(value)))
+            .collect()
+    }
+}
+
+/// Lazily groups the entries of a tree iterator into [`StateChunk`]s.
+#[derive(Debug)]
+pub struct StateChunkIter<I> {
+    inner: I,
+    version: Version,
+    root_hash: RootHash,
+    chunk_size: usize,
+    index: u64,
+}
+
+impl<I> StateChunkIter<I> {
+    pub fn new(inner: I, version: Version, root_hash: RootHash, chunk_size: usize) -> Result<Self> {
+        if chunk_size == 0 {
+            return Err(LeftRightTrieError::InvalidChunkSize);
+        }
+
+        Ok(Self {
+            inner,
+            version,
+            root_hash,
+            chunk_size,
+            index: 0,
+        })
+    }
+}
+
+impl<I, E> Iterator for StateChunkIter<I>
+where
+    I: Iterator<Item = std::result::Result<(KeyHash, OwnedValue), E>>,
+    E: Display,
+{
+    type Item = Result<StateChunk>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut entries = Vec::new();
+        for item in self.inner.by_ref().take(self.chunk_size) {
+            match item {
+                Ok((key, value)) => entries.push((key.0, value)),
+                Err(err) => return Some(Err(LeftRightTrieError::Io(err.to_string()))),
+            }
+        }
+        if entries.is_empty() {
+            return None;
+        }
+
+        let chunk = StateChunk {
+            version: self.version,
+            root_hash: self.root_hash.0,
+            index: self.index,
+            entries,
+        };
+        self.index += 1;
+
+        Some(Ok(chunk))
+    }
+}
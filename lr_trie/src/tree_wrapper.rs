@@ -1,32 +1,57 @@
-use std::fmt::{self, Debug, Display, Formatter};
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Debug, Display, Formatter},
+    marker::PhantomData,
+    sync::Arc,
+};
 
 pub use left_right::ReadHandleFactory;
 use patriecia::{
-    JellyfishMerkleIterator, JellyfishMerkleTree, KeyHash, RootHash, Sha256, SimpleHasher,
+    JellyfishMerkleIterator, JellyfishMerkleTree, KeyHash, OwnedValue, RootHash, SimpleHasher,
     SparseMerkleProof, TreeReader, TreeWriter, Version, VersionedDatabase, VersionedTrie,
 };
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
-use crate::{LeftRightTrieError, Result};
+use crate::{
+    span::span, BincodeCodec, Codec, LeftRightTrieError, NamedHasher, NoopMetrics, PinnedView,
+    ProofBundle, Result, StateChunkIter, StorageMetrics, EMPTY_ROOT_HASH,
+};
 
 pub type Proof = Vec<u8>;
 
 #[derive(Debug, Clone)]
-pub struct JellyfishMerkleTreeWrapper<D, H>
+pub struct JellyfishMerkleTreeWrapper<D, H, C = BincodeCodec>
 where
     D: TreeReader + TreeWriter + VersionedDatabase,
     H: SimpleHasher,
+    C: Codec,
 {
     inner: JellyfishMerkleTree<D, H>,
+    metrics: Arc<dyn StorageMetrics>,
+    _codec: PhantomData<C>,
 }
 
-impl<D, H> JellyfishMerkleTreeWrapper<D, H>
+impl<D, H, C> JellyfishMerkleTreeWrapper<D, H, C>
 where
     D: TreeReader + TreeWriter + VersionedDatabase,
     H: SimpleHasher,
+    C: Codec,
 {
     pub fn new(inner: JellyfishMerkleTree<D, H>) -> Self {
-        Self { inner }
+        Self {
+            inner,
+            metrics: Arc::new(NoopMetrics),
+            _codec: PhantomData,
+        }
+    }
+
+    /// Report the reads served by this wrapper to `metrics`.
+    ///
+    /// Handles obtained from `LeftRightTrie::handle` already report to the trie's sink;
+    /// readers built from a `ReadHandleFactory` can attach it with this.
+    pub fn with_metrics(mut self, metrics: Arc<dyn StorageMetrics>) -> Self {
+        self.metrics = metrics;
+        self
     }
 
     /// Produces a clone of the underlying trie
@@ -35,26 +60,39 @@ where
     }
 
     /// Get the value associated with a key at a specified `Version`.
+    ///
+    /// Fails with [`LeftRightTrieError::NotFound`] if the key has no value; use
+    /// [`JellyfishMerkleTreeWrapper::get_opt`] when absence is expected.
     pub fn get<K, V>(&self, key: &K, version: Version) -> Result<V>
     where
         K: for<'b> Deserialize<'b> + Serialize + Clone,
         V: for<'b> Deserialize<'b> + Serialize + Clone,
     {
-        let key = KeyHash::with::<Sha256>(bincode::serialize(&key).unwrap_or_default());
-
-        let raw_value_opt = self
-            .inner
-            .get(key, version)
-            .map_err(|err| LeftRightTrieError::Other(err.to_string()))?;
-
-        let raw_value = raw_value_opt.ok_or_else(|| {
-            LeftRightTrieError::Other("received none value from inner trie".to_string())
-        })?;
+        match self.get_opt(key, version)? {
+            Some(value) => Ok(value),
+            None => Err(LeftRightTrieError::NotFound(format!(
+                "{:?}",
+                KeyHash::with::<H>(C::encode_key(key)?)
+            ))),
+        }
+    }
 
-        let value = bincode::deserialize::<V>(&raw_value)
-            .map_err(|err| LeftRightTrieError::Other(err.to_string()))?;
+    /// Get the value associated with a key at a specified `Version`, or `None` if the
+    /// key has no value at that version.
+    pub fn get_opt<K, V>(&self, key: &K, version: Version) -> Result<Option<V>>
+    where
+        K: for<'b> Deserialize<'b> + Serialize + Clone,
+        V: for<'b> Deserialize<'b> + Serialize + Clone,
+    {
+        self.check_version(version)?;
+        self.metrics.record_read();
+        let key = KeyHash::with::<H>(C::encode_key(&key)?);
 
-        Ok(value)
+        self.inner
+            .get(key, version)
+            .map_err(|err| LeftRightTrieError::Io(err.to_string()))?
+            .map(|raw_value| C::decode::<V>(&raw_value))
+            .transpose()
     }
 
     /// Returns true if the inner tree contains the specified key at `Version`.
@@ -62,10 +100,12 @@ where
     where
         K: Serialize + Deserialize<'b>,
     {
-        let key = KeyHash::with::<Sha256>(bincode::serialize(&key).unwrap_or_default());
+        self.check_version(version)?;
+        self.metrics.record_read();
+        let key = KeyHash::with::<H>(C::encode_key(&key)?);
         self.inner
             .contains(key, version)
-            .map_err(|err| LeftRightTrieError::Other(err.to_string()))
+            .map_err(|err| LeftRightTrieError::Io(err.to_string()))
     }
 
     /// Insert a key-value pair into the tree at a specified `Version` and update the database
@@ -75,8 +115,8 @@ where
         K: Serialize + Deserialize<'b>,
         V: Serialize + Deserialize<'b>,
     {
-        let key = KeyHash::with::<Sha256>(bincode::serialize(&key).unwrap_or_default());
-        let value = bincode::serialize(&value).unwrap_or_default();
+        let key = KeyHash::with::<H>(C::encode_key(&key)?);
+        let value = C::encode(&value)?;
 
         match self
             .inner
@@ -86,8 +126,8 @@ where
                 .inner
                 .reader()
                 .write_node_batch(&batch.node_batch)
-                .map_err(|err| LeftRightTrieError::Other(err.to_string())),
-            Err(err) => Err(LeftRightTrieError::Other(err.to_string())),
+                .map_err(|err| LeftRightTrieError::Io(err.to_string())),
+            Err(err) => Err(LeftRightTrieError::Io(err.to_string())),
         }
     }
 
@@ -99,27 +139,34 @@ where
     where
         K: Serialize + Deserialize<'b>,
     {
-        let key = KeyHash::with::<Sha256>(bincode::serialize(&key).unwrap_or_default());
+        let key = KeyHash::with::<H>(C::encode_key(&key)?);
         let version = self.version() + 1;
         match self.inner.put_value_set(vec![(key, None)], version) {
             Ok((_, batch)) => self
                 .inner
                 .reader()
                 .write_node_batch(&batch.node_batch)
-                .map_err(|err| LeftRightTrieError::Other(err.to_string()))?,
-            Err(err) => return Err(LeftRightTrieError::Other(err.to_string())),
+                .map_err(|err| LeftRightTrieError::Io(err.to_string()))?,
+            Err(err) => return Err(LeftRightTrieError::Io(err.to_string())),
         }
         Ok(!self
             .inner
             .contains(key, version)
-            .map_err(|err| LeftRightTrieError::Other(err.to_string()))?)
+            .map_err(|err| LeftRightTrieError::Io(err.to_string()))?)
     }
 
     /// Get the `RootHash` of a `JellyfishMerkleTree` at a specified `Version`.
+    ///
+    /// Version 0 has no root node and is reported as [`EMPTY_ROOT_HASH`].
     pub fn root_hash(&self, version: Version) -> Result<RootHash> {
+        self.check_version(version)?;
+        self.metrics.record_read();
+        if version == 0 {
+            return Ok(EMPTY_ROOT_HASH);
+        }
         self.inner
             .get_root_hash(version)
-            .map_err(|err| LeftRightTrieError::Other(err.to_string()))
+            .map_err(|err| LeftRightTrieError::Io(err.to_string()))
     }
 
     /// Creates a Merkle proof for a given value.
@@ -127,10 +174,13 @@ where
     where
         K: Serialize + Deserialize<'b>,
     {
-        let key = KeyHash::with::<Sha256>(bincode::serialize(&key).unwrap_or_default());
+        self.check_version(version)?;
+        span!("get_proof", version);
+        self.metrics.record_read();
+        let key = KeyHash::with::<H>(C::encode_key(&key)?);
         self.inner
             .get_proof(key, version)
-            .map_err(|err| LeftRightTrieError::Other(err.to_string()))
+            .map_err(|err| LeftRightTrieError::Proof(err.to_string()))
     }
 
     /// Verifies a Merkle proof for a given value.
@@ -146,15 +196,137 @@ where
     {
         self.inner
             .verify_proof(element_key, version, expected_root_hash, proof)
-            .map_err(|err| LeftRightTrieError::Other(err.to_string()))
+            .map_err(|err| LeftRightTrieError::Proof(err.to_string()))
+    }
+
+    /// Creates a Merkle proof that no value is stored for `key` at `version`.
+    ///
+    /// Fails if the key is present, since the resulting proof would be an inclusion proof.
+    pub fn get_proof_of_absence<'b, K>(
+        &self,
+        key: &K,
+        version: Version,
+    ) -> Result<SparseMerkleProof<H>>
+    where
+        K: Serialize + Deserialize<'b>,
+    {
+        self.check_version(version)?;
+        span!("get_proof_of_absence", version);
+        self.metrics.record_read();
+        let key = KeyHash::with::<H>(C::encode_key(&key)?);
+        let contains = self
+            .inner
+            .contains(key, version)
+            .map_err(|err| LeftRightTrieError::Io(err.to_string()))?;
+        if contains {
+            return Err(LeftRightTrieError::Proof(format!(
+                "key {key:?} is present at version {version}"
+            )));
+        }
+
+        self.inner
+            .get_proof(key, version)
+            .map_err(|err| LeftRightTrieError::Proof(err.to_string()))
+    }
+
+    /// Verifies that `proof` shows no value is stored for `key` under `expected_root_hash`.
+    pub fn verify_absence<'b, K>(
+        &self,
+        key: &K,
+        expected_root_hash: RootHash,
+        proof: &SparseMerkleProof<H>,
+    ) -> Result<()>
+    where
+        K: Serialize + Deserialize<'b>,
+    {
+        let key = KeyHash::with::<H>(C::encode_key(&key)?);
+        proof
+            .verify_nonexistence(expected_root_hash, key)
+            .map_err(|err| LeftRightTrieError::Proof(err.to_string()))
     }
 
     /// Create a [`JellyfishMerkleIterator`] from the reader: R, to iterate
     /// over values in the tree starting at the given key and version.
     pub fn iter(&self, version: Version) -> Result<JellyfishMerkleIterator<D>> {
+        self.check_version(version)?;
+        self.metrics.record_read();
         self.inner
             .iter(version)
-            .map_err(|err| LeftRightTrieError::Other(err.to_string()))
+            .map_err(|err| LeftRightTrieError::Io(err.to_string()))
+    }
+
+    /// Iterate over the key hashes stored at `version` without decoding any values.
+    pub fn keys(&self, version: Version) -> Result<impl Iterator<Item = Result<KeyHash>>> {
+        Ok(self.iter(version)?.map(|entry| {
+            entry
+                .map(|(key, _)| key)
+                .map_err(|err| LeftRightTrieError::Io(err.to_string()))
+        }))
+    }
+
+    /// Iterate over the values stored at `version`, in key hash order.
+    pub fn values<V>(&self, version: Version) -> Result<impl Iterator<Item = Result<V>>>
+    where
+        V: DeserializeOwned,
+    {
+        Ok(self.iter(version)?.map(|entry| {
+            let (_, value) = entry.map_err(|err| LeftRightTrieError::Io(err.to_string()))?;
+            C::decode(&value)
+        }))
+    }
+
+    /// Returns every key whose value differs between `from_version` and `to_version`,
+    /// with its value at each of the two versions, in key hash order.
+    ///
+    /// Version 0, which no write has produced a root for, is treated as the empty tree.
+    ///
+    /// # Cost
+    ///
+    /// This walks the whole tree at both versions and holds both in memory, so each call
+    /// costs O(state) time and memory no matter how few keys changed. It is meant for
+    /// audits and tests, not for diffing every block on a large tree.
+    pub fn diff(
+        &self,
+        from_version: Version,
+        to_version: Version,
+    ) -> Result<Vec<(KeyHash, Option<OwnedValue>, Option<OwnedValue>)>> {
+        let entries_at = |version| -> Result<BTreeMap<KeyHash, OwnedValue>> {
+            if version == 0 {
+                return Ok(BTreeMap::new());
+            }
+            self.iter(version)?
+                .map(|entry| entry.map_err(|err| LeftRightTrieError::Io(err.to_string())))
+                .collect()
+        };
+        let from = entries_at(from_version)?;
+        let mut to = entries_at(to_version)?;
+
+        let mut changes = Vec::new();
+        for (key, old_value) in from {
+            match to.remove(&key) {
+                Some(new_value) if new_value == old_value => {}
+                new_value => changes.push((key, Some(old_value), new_value)),
+            }
+        }
+        changes.extend(to.into_iter().map(|(key, value)| (key, None, Some(value))));
+        changes.sort_by_key(|(key, _, _)| *key);
+
+        Ok(changes)
+    }
+
+    /// Split the tree at `version` into [`StateChunk`](crate::StateChunk)s of at most
+    /// `chunk_size` entries, in key order, to be shipped to a syncing peer.
+    pub fn export_chunks(
+        &self,
+        version: Version,
+        chunk_size: usize,
+    ) -> Result<StateChunkIter<JellyfishMerkleIterator<D>>> {
+        StateChunkIter::new(
+            self.iter(version)?,
+            version,
+            self.root_hash(version)?,
+            chunk_size,
+        )
     }
 
     /// Get the number of `Some(value)`s from the latest version of the tree stored in the `VersionedDatabase`.
@@ -173,16 +345,67 @@ where
         self.inner.version()
     }
 
+    /// Pin reads to `version`, so that several reads see the same state even if newer
+    /// versions are written in the meantime.
+    pub fn pin(&self, version: Version) -> Result<PinnedView<D, H, C>> {
+        self.check_version(version)?;
+        Ok(PinnedView::new(self.clone_handle(), version))
+    }
+
     /// Returns a clone of the value history from the database.
     pub fn value_history(&self) -> <D as VersionedDatabase>::HistoryIter {
         self.inner.reader().value_history()
     }
+
+    /// Clones the wrapper and its metrics sink without requiring `D: Clone`.
+    fn clone_handle(&self) -> Self {
+        Self {
+            inner: self.inner(),
+            metrics: self.metrics.clone(),
+            _codec: PhantomData,
+        }
+    }
+
+    fn check_version(&self, version: Version) -> Result<()> {
+        let latest = self.version();
+        if version > latest {
+            return Err(LeftRightTrieError::VersionTooNew {
+                requested: version,
+                latest,
+            });
+        }
+
+        Ok(())
+    }
 }
 
-impl<D, H> Display for JellyfishMerkleTreeWrapper<D, H>
+impl<D, H, C> JellyfishMerkleTreeWrapper<D, H, C>
+where
+    D: TreeReader + TreeWriter + VersionedDatabase,
+    H: NamedHasher,
+    C: Codec,
+{
+    /// Creates a Merkle proof for a given value, packaged with the data needed to verify it.
+    pub fn get_proof_bundle<'b, K>(&self, key: &K, version: Version) -> Result<ProofBundle>
+    where
+        K: Serialize + Deserialize<'b>,
+    {
+        span!("get_proof_bundle", version);
+        let key = KeyHash::with::<H>(C::encode_key(&key)?);
+        let proof = self
+            .inner
+            .get_proof(key, version)
+            .map_err(|err| LeftRightTrieError::Proof(err.to_string()))?;
+
+        ProofBundle::new(key, version, self.root_hash(version)?, &proof)
+    }
+}
+
+impl<D, H, C> Display for JellyfishMerkleTreeWrapper<D, H, C>
 where
     D: TreeReader + TreeWriter + VersionedDatabase,
     H: SimpleHasher,
+    C: Codec,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "{:?}", self.inner)
@@ -193,7 +416,7 @@ where
 mod tests {
     use std::sync::Arc;
 
-    use patriecia::MockTreeStore;
+    use patriecia::{MockTreeStore, Sha256};
 
     use super::*;
 
@@ -201,7 +424,7 @@ mod tests {
     fn test_wrapper_can_add_remove_values() {
         let db = Arc::new(MockTreeStore::default());
         let jmt = JellyfishMerkleTree::<_, Sha256>::new(db);
-        let mut wrapper = JellyfishMerkleTreeWrapper::new(jmt);
+        let mut wrapper = JellyfishMerkleTreeWrapper::<_, Sha256>::new(jmt);
 
         let key = "Ada Lovelace";
         let value = "Analytical Engine";
@@ -219,4 +442,192 @@ mod tests {
             2 /* there are two total transactions */
         );
     }
+
+    #[test]
+    fn test_wrapper_can_prove_absence() {
+        let db = Arc::new(MockTreeStore::default());
+        let jmt = JellyfishMerkleTree::<_, Sha256>::new(db);
+        let mut wrapper = JellyfishMerkleTreeWrapper::<_, Sha256>::new(jmt);
+
+        wrapper.insert("Ada Lovelace", "Analytical Engine").unwrap();
+        let root = wrapper.root_hash(1).unwrap();
+
+        assert!(wrapper.get_proof_of_absence(&"Ada Lovelace", 1).is_err());
+
+        let proof = wrapper.get_proof_of_absence(&"Charles Babbage", 1).unwrap();
+        wrapper
+            .verify_absence(&"Charles Babbage", root, &proof)
+            .unwrap();
+        assert!(wrapper
+            .verify_absence(&"Ada Lovelace", root, &proof)
+            .is_err());
+    }
+
+    #[test]
+    fn test_wrapper_returns_structured_errors() {
+        let db = Arc::new(MockTreeStore::default());
+        let jmt = JellyfishMerkleTree::<_, Sha256>::new(db);
+        let mut wrapper = JellyfishMerkleTreeWrapper::<_, Sha256>::new(jmt);
+
+        wrapper.insert("Ada Lovelace", "Analytical Engine").unwrap();
+
+        assert!(matches!(
+            wrapper.get::<String, String>(&"Charles Babbage".to_string(), 1),
+            Err(LeftRightTrieError::NotFound(_))
+        ));
+        assert_eq!(
+            wrapper.root_hash(2),
+            Err(LeftRightTrieError::VersionTooNew {
+                requested: 2,
+                latest: 1
+            })
+        );
+        assert!(matches!(
+            wrapper.get_proof_of_absence(&"Ada Lovelace", 1),
+            Err(LeftRightTrieError::Proof(_))
+        ));
+    }
+
+    #[test]
+    fn test_wrapper_iterates_keys_and_values() {
+        let db = Arc::new(MockTreeStore::default());
+        let jmt = JellyfishMerkleTree::<_, Sha256>::new(db);
+        let mut wrapper = JellyfishMerkleTreeWrapper::<_, Sha256>::new(jmt);
+
+        wrapper.insert("Ada Lovelace", 1u64).unwrap();
+        wrapper.insert("Charles Babbage", 2u64).unwrap();
+
+        let keys = wrapper
+            .keys(2)
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        let mut expected = vec![hash("Ada Lovelace"), hash("Charles Babbage")];
+        expected.sort();
+        assert_eq!(keys, expected);
+
+        let mut values = wrapper
+            .values::<u64>(2)
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        values.sort();
+        assert_eq!(values, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_pinned_view_ignores_newer_versions() {
+        let db = Arc::new(MockTreeStore::default());
+        let jmt = JellyfishMerkleTree::<_, Sha256>::new(db);
+        let mut wrapper = JellyfishMerkleTreeWrapper::<_, Sha256>::new(jmt);
+
+        wrapper.insert("Ada Lovelace", 1u64).unwrap();
+        let pinned = wrapper.pin(1).unwrap();
+        let root = pinned.root_hash().unwrap();
+
+        wrapper.insert("Ada Lovelace", 2u64).unwrap();
+        wrapper.insert("Charles Babbage", 3u64).unwrap();
+
+        let keys = ["Ada Lovelace".to_string(), "Charles Babbage".to_string()];
+        assert_eq!(pinned.batch_get::<_, u64>(&keys), Ok(vec![Some(1), None]));
+        assert_eq!(pinned.root_hash(), Ok(root));
+        assert_eq!(wrapper.get::<_, u64>(&keys[0], 3), Ok(2));
+        assert!(matches!(
+            wrapper.pin(4),
+            Err(LeftRightTrieError::VersionTooNew { .. })
+        ));
+    }
+
+    #[test]
+    fn test_wrapper_proof_bundle_roundtrip() {
+        let db = Arc::new(MockTreeStore::default());
+        let jmt = JellyfishMerkleTree::<_, Sha256>::new(db);
+        let mut wrapper = JellyfishMerkleTreeWrapper::<_, Sha256>::new(jmt);
+
+        wrapper.insert("Ada Lovelace", "Analytical Engine").unwrap();
+
+        let bundle = wrapper.get_proof_bundle(&"Ada Lovelace", 1).unwrap();
+        let decoded = ProofBundle::from_bytes(&bundle.to_bytes().unwrap()).unwrap();
+        assert_eq!(decoded, bundle);
+        assert_eq!(decoded.root_hash(), wrapper.root_hash(1).unwrap());
+
+        let proof = decoded.proof::<Sha256>().unwrap();
+        wrapper
+            .verify_proof::<&str>(decoded.key_hash(), 1, decoded.root_hash(), proof)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_wrapper_diff_between_versions() {
+        let db = Arc::new(MockTreeStore::default());
+        let jmt = JellyfishMerkleTree::<_, Sha256>::new(db);
+        let mut wrapper = JellyfishMerkleTreeWrapper::<_, Sha256>::new(jmt);
+
+        wrapper.insert("Ada Lovelace", "Analytical Engine").unwrap();
+        wrapper
+            .insert("Charles Babbage", "Difference Engine")
+            .unwrap();
+        wrapper.insert("Ada Lovelace", "Notes").unwrap();
+        wrapper.remove("Charles Babbage").unwrap();
+        wrapper.insert("Alan Turing", "Bombe").unwrap();
+
+        let diff = wrapper.diff(2, 5).unwrap();
+        assert_eq!(diff.len(), 3);
+        for (key, old_value, new_value) in diff {
+            let (expected_old, expected_new) = if key == hash("Ada Lovelace") {
+                (Some("Analytical Engine"), Some("Notes"))
+            } else if key == hash("Charles Babbage") {
+                (Some("Difference Engine"), None)
+            } else {
+                assert_eq!(key, hash("Alan Turing"));
+                (None, Some("Bombe"))
+            };
+            assert_eq!(old_value, expected_old.map(encode));
+            assert_eq!(new_value, expected_new.map(encode));
+        }
+
+        assert!(wrapper.diff(5, 5).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_wrapper_diff_from_the_empty_tree() {
+        let db = Arc::new(MockTreeStore::default());
+        let jmt = JellyfishMerkleTree::<_, Sha256>::new(db);
+        let mut wrapper = JellyfishMerkleTreeWrapper::<_, Sha256>::new(jmt);
+        assert!(wrapper.diff(0, 0).unwrap().is_empty());
+
+        wrapper.insert("Ada Lovelace", "Analytical Engine").unwrap();
+        wrapper
+            .insert("Charles Babbage", "Difference Engine")
+            .unwrap();
+
+        let mut expected = vec![
+            (
+                hash("Ada Lovelace"),
+                None,
+                Some(encode("Analytical Engine")),
+            ),
+            (
+                hash("Charles Babbage"),
+                None,
+                Some(encode("Difference Engine")),
+            ),
+        ];
+        expected.sort_by_key(|(key, _, _)| *key);
+        assert_eq!(wrapper.diff(0, 2).unwrap(), expected);
+
+        let reversed = expected
+            .into_iter()
+            .map(|(key, old_value, new_value)| (key, new_value, old_value))
+            .collect::<Vec<_>>();
+        assert_eq!(wrapper.diff(2, 0).unwrap(), reversed);
+    }
+
+    fn hash(key: &str) -> KeyHash {
+        KeyHash::with::<Sha256>(encode(key))
+    }
+
+    fn encode(value: &str) -> Vec<u8> {
+        BincodeCodec::encode(value).unwrap()
+    }
 }
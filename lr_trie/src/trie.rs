@@ -1,39 +1,75 @@
 use std::{
+    collections::HashMap,
     fmt::{self, Debug, Display, Formatter},
     marker::PhantomData,
     sync::Arc,
+    time::{Duration, Instant},
 };
 
 pub use left_right::ReadHandleFactory;
 use left_right::{ReadHandle, WriteHandle};
 use patriecia::{
-    JellyfishMerkleTree, KeyHash, RootHash, Sha256, SimpleHasher, SparseMerkleProof, TreeReader,
-    TreeWriter, Version, VersionedDatabase,
+    JellyfishMerkleIterator, JellyfishMerkleTree, KeyHash, OwnedValue, RootHash, SimpleHasher,
+    SparseMerkleProof, TreeReader, TreeWriter, Version, VersionedDatabase, VersionedTrie,
 };
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
-use crate::{JellyfishMerkleTreeWrapper, LeftRightTrieError, Operation, Result};
+use crate::{
+    span::span, BincodeCodec, Codec, JellyfishMerkleTreeWrapper, LeftRightTrieError, NamedHasher,
+    NoopMetrics, Operation, ProofBundle, Result, SpeculativeStore, StateChunk, StateChunkIter,
+    StorageMetrics, TypedTrieHandle, WriterView,
+};
+
+/// The root hash of a tree without any values.
+///
+/// A tree that has never been written to has no root node at all, so `root(0)` and
+/// `root_latest` on a fresh trie report this hash, matching the root of a tree whose
+/// values have all been removed.
+pub const EMPTY_ROOT_HASH: RootHash = RootHash(*b"SPARSE_MERKLE_PLACEHOLDER_HASH__");
+
+/// Determines when operations appended with the `*_deferred` methods are published to readers.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PublishPolicy {
+    /// Deferred operations are only published by an explicit call to `LeftRightTrie::publish`.
+    #[default]
+    Manual,
+    /// Publish once the given number of operations are pending.
+    EveryNOps(usize),
+    /// Publish on the first append after the given duration has elapsed since the last publish.
+    ///
+    /// There is no background timer, so pending operations are not published until
+    /// the next append or an explicit `publish`.
+    Interval(Duration),
+}
 
 /// Concurrent generic JellyfishMerkleTree.
 #[derive(Debug)]
-pub struct LeftRightTrie<'a, K, V, D, H>
+pub struct LeftRightTrie<'a, K, V, D, H, C = BincodeCodec>
 where
     D: TreeReader + TreeWriter + VersionedDatabase,
     H: SimpleHasher,
     K: Serialize + Deserialize<'a>,
     V: Serialize + Deserialize<'a>,
+    C: Codec,
 {
     pub read_handle: ReadHandle<JellyfishMerkleTree<D, H>>,
     pub write_handle: WriteHandle<JellyfishMerkleTree<D, H>, Operation>,
-    _marker: PhantomData<(K, V, &'a ())>,
+    metrics: Arc<dyn StorageMetrics>,
+    publish_policy: PublishPolicy,
+    pending_ops: usize,
+    staged: HashMap<KeyHash, Option<OwnedValue>>,
+    last_publish: Instant,
+    max_version: Version,
+    _marker: PhantomData<(K, V, C, &'a ())>,
 }
 
-impl<'a, D, K, V, H> LeftRightTrie<'a, K, V, D, H>
+impl<'a, D, K, V, H, C> LeftRightTrie<'a, K, V, D, H, C>
 where
     D: TreeReader + TreeWriter + VersionedDatabase,
     H: SimpleHasher,
     K: Serialize + Deserialize<'a>,
     V: Serialize + Deserialize<'a>,
+    C: Codec,
 {
     pub fn new(db: Arc<D>) -> Self {
         let (write_handle, read_handle) = left_right::new_from_empty(JellyfishMerkleTree::new(db));
@@ -41,18 +77,73 @@ where
         Self {
             read_handle,
             write_handle,
+            metrics: Arc::new(NoopMetrics),
+            publish_policy: PublishPolicy::default(),
+            pending_ops: 0,
+            staged: HashMap::new(),
+            last_publish: Instant::now(),
+            max_version: Version::MAX,
             _marker: PhantomData,
         }
     }
 
+    /// Replace the [`StorageMetrics`] sink that reads, writes and publishes are reported to.
+    pub fn set_metrics(&mut self, metrics: Arc<dyn StorageMetrics>) {
+        self.metrics = metrics;
+    }
+
+    /// The [`StorageMetrics`] sink of this trie, for attaching to readers built from a
+    /// [`ReadHandleFactory`] with [`JellyfishMerkleTreeWrapper::with_metrics`].
+    pub fn metrics(&self) -> Arc<dyn StorageMetrics> {
+        self.metrics.clone()
+    }
+
+    /// Set the [`PublishPolicy`] applied to operations appended with the `*_deferred` methods.
+    pub fn set_publish_policy(&mut self, policy: PublishPolicy) {
+        self.publish_policy = policy;
+    }
+
+    /// Set the highest version the trie may reach.
+    ///
+    /// Operations that would produce a version above it are rejected with
+    /// [`LeftRightTrieError::VersionLimitReached`] instead of being appended.
+    pub fn set_max_version(&mut self, max_version: Version) {
+        self.max_version = max_version;
+    }
+
+    /// Returns a [`WriterView`] that can read operations appended but not yet published.
+    pub fn writer_view(&self) -> WriterView<'_, 'a, K, V, D, H, C>
+    where
+        V: DeserializeOwned,
+    {
+        WriterView::new(self)
+    }
+
+    /// Returns the number of operations appended since the last publish.
+    pub fn pending_operations(&self) -> usize {
+        self.pending_ops
+    }
+
     // TODO: revist and discuss Default implementations of JellyfishMerkleTree
-    pub fn handle(&self) -> JellyfishMerkleTreeWrapper<D, H> {
+    pub fn handle(&self) -> JellyfishMerkleTreeWrapper<D, H, C> {
         JellyfishMerkleTreeWrapper::new(
             self.read_handle
                 .enter()
                 .map(|guard| guard.clone())
                 .unwrap_or_default(),
         )
+        .with_metrics(self.metrics.clone())
+    }
+
+    /// Like [`LeftRightTrie::handle`], but fails with [`LeftRightTrieError::Poisoned`]
+    /// instead of falling back to an empty tree if the write handle has been dropped.
+    pub fn try_handle(&self) -> Result<JellyfishMerkleTreeWrapper<D, H, C>> {
+        self.read_handle
+            .enter()
+            .map(|guard| {
+                JellyfishMerkleTreeWrapper::new(guard.clone()).with_metrics(self.metrics.clone())
+            })
+            .ok_or(LeftRightTrieError::Poisoned)
     }
 
     /// Returns a clone of the value history from the database.
@@ -65,23 +156,39 @@ where
     /// Returns the number of `Some` values within `value_history`
     /// for all keys at the latest version in the database.
     pub fn len(&self) -> Result<usize> {
-        Ok(self.handle().len())
+        Ok(self.try_handle()?.len())
     }
 
     /// Returns true if there are no nodes with `OwnedValue`s for the latest
     /// `Version` in `VersionedDatabase::value_history()`
     pub fn is_empty(&self) -> Result<bool> {
-        Ok(self.handle().is_empty())
+        Ok(self.try_handle()?.is_empty())
     }
 
     /// Get the `RootHash` of a `JellyfishMerkleTree` at a specified `Version`.
+    ///
+    /// The root is read from the root node the database keeps for `version`, so historical
+    /// roots are never recomputed and are still available after a persistent database is
+    /// reopened. Version 0 is reported as [`EMPTY_ROOT_HASH`].
     pub fn root(&self, version: Version) -> Result<RootHash> {
-        self.handle().root_hash(version)
+        self.try_handle()?.root_hash(version)
+    }
+
+    /// Get the `RootHash` of every version in `from..=to`, in version order.
+    ///
+    /// Every root is looked up like [`LeftRightTrie::root`], including version 0 as
+    /// [`EMPTY_ROOT_HASH`], and through a single read handle so that all of them come
+    /// from the same snapshot.
+    pub fn roots_range(&self, from: Version, to: Version) -> Result<Vec<(Version, RootHash)>> {
+        let handle = self.try_handle()?;
+        (from..=to)
+            .map(|version| -> Result<_> { Ok((version, handle.root_hash(version)?)) })
+            .collect()
     }
 
     /// Get the latest `Version` of the tree known to the database.
     pub fn version(&self) -> Result<Version> {
-        Ok(self.handle().version())
+        Ok(self.try_handle()?.version())
     }
 
     /// Get the `RootHash` at the latest `Version`.
@@ -94,9 +201,7 @@ where
     where
         K: Serialize + Deserialize<'a>,
     {
-        self.handle()
-            .get_proof::<K>(key, version)
-            .map_err(|err| LeftRightTrieError::Other(err.to_string()))
+        self.try_handle()?.get_proof::<K>(key, version)
     }
 
     /// Verify a `SparseMerkleProof` at a specified `Version`.
@@ -110,9 +215,107 @@ where
     where
         K: Serialize + Deserialize<'a>,
     {
-        self.handle()
+        self.try_handle()?
             .verify_proof::<K>(element_key, version, expected_root_hash, proof)
-            .map_err(|err| LeftRightTrieError::Other(err.to_string()))
+    }
+
+    /// Get a `SparseMerkleProof` that `key` has no value at a specified `Version`.
+    pub fn get_proof_of_absence(&self, key: &K, version: Version) -> Result<SparseMerkleProof<H>> {
+        self.try_handle()?.get_proof_of_absence(key, version)
+    }
+
+    /// Verify a `SparseMerkleProof` that `key` has no value under `expected_root_hash`.
+    pub fn verify_absence(
+        &self,
+        key: &K,
+        expected_root_hash: RootHash,
+        proof: &SparseMerkleProof<H>,
+    ) -> Result<()> {
+        self.try_handle()?
+            .verify_absence(key, expected_root_hash, proof)
+    }
+
+    /// Returns every key whose value differs between two versions, with its value at each.
+    ///
+    /// Scans the whole tree at both versions; see [`JellyfishMerkleTreeWrapper::diff`]
+    /// for the cost.
+    pub fn diff(
+        &self,
+        from_version: Version,
+        to_version: Version,
+    ) -> Result<Vec<(KeyHash, Option<OwnedValue>, Option<OwnedValue>)>> {
+        self.try_handle()?.diff(from_version, to_version)
+    }
+
+    /// Split the tree at `version` into [`StateChunk`]s of at most `chunk_size` entries.
+    pub fn export_chunks(
+        &self,
+        version: Version,
+        chunk_size: usize,
+    ) -> Result<StateChunkIter<JellyfishMerkleIterator<D>>> {
+        self.try_handle()?.export_chunks(version, chunk_size)
+    }
+
+    /// Rebuild an empty trie from the [`StateChunk`]s produced by `export_chunks` on a peer.
+    ///
+    /// All chunks are applied and published as a single new version. Nothing is written
+    /// if the chunks are empty, out of order, come from different exports, or if the root
+    /// hash they rebuild does not match the root hash they were exported from.
+    ///
+    /// The rebuilt root is checked before anything is written, which needs a copy of the
+    /// whole value set, so peak memory use is about twice the size of the imported state.
+    pub fn import_chunks<I>(&mut self, chunks: I) -> Result<RootHash>
+    where
+        I: IntoIterator<Item = StateChunk>,
+    {
+        if self.pending_ops > 0 || !self.is_empty()? {
+            return Err(LeftRightTrieError::NotEmpty);
+        }
+
+        let mut chunks = chunks.into_iter().peekable();
+        let Some(first) = chunks.peek() else {
+            return Err(LeftRightTrieError::InvalidChunks(
+                "no state chunks to import".to_string(),
+            ));
+        };
+        let (export_version, expected_root) = (first.version, first.root_hash());
+
+        let mut value_set = Vec::new();
+        for (index, chunk) in chunks.enumerate() {
+            if chunk.index != index as u64 {
+                return Err(LeftRightTrieError::InvalidChunks(format!(
+                    "expected state chunk {index}, got {}",
+                    chunk.index
+                )));
+            }
+            if chunk.version != export_version || chunk.root_hash() != expected_root {
+                return Err(LeftRightTrieError::InvalidChunks(
+                    "state chunks belong to different exports".to_string(),
+                ));
+            }
+            value_set.extend(chunk.into_value_set());
+        }
+
+        // Compute the root the chunks rebuild without writing anything, so a tampered
+        // chunk set is never published.
+        let version = self.next_version()?;
+        let (root, _) = self
+            .try_handle()?
+            .inner()
+            .put_value_set(value_set.clone(), version + 1)
+            .map_err(|err| LeftRightTrieError::Io(err.to_string()))?;
+        if root != expected_root {
+            return Err(LeftRightTrieError::RootMismatch {
+                expected: expected_root,
+                actual: root,
+            });
+        }
+
+        let batch_size = value_set.len();
+        self.append(Operation::Extend(value_set, version), batch_size);
+        self.publish();
+
+        Ok(root)
     }
 
     /// Create a ReadHandleFactory which is Send & Sync and can be shared
@@ -122,7 +325,7 @@ where
     }
 
     /// Wrapper for `LeftRightTrie::insert`.
-    pub fn update(&mut self, key: K, value: V) {
+    pub fn update(&mut self, key: K, value: V) -> Result<()> {
         self.insert(key, value)
     }
 
@@ -133,66 +336,252 @@ where
     /// This can take some time, especially if readers are executing slow operations,
     /// or if there are many of them.
     pub fn publish(&mut self) {
+        span!("publish", pending_ops = self.pending_ops);
+        let start = Instant::now();
         self.write_handle.publish();
+        self.metrics.record_publish(start.elapsed());
+        self.pending_ops = 0;
+        self.staged.clear();
+        self.last_publish = Instant::now();
     }
 
     /// Add and publish a key-value pair at a specified version.
-    pub fn insert(&mut self, key: K, value: V) {
-        //TODO: revisit the serializer used to store things on the trie
-        let keyhash = KeyHash::with::<Sha256>(bincode::serialize(&key).unwrap_or_default());
-        let owned_value = bincode::serialize(&value).unwrap_or_default();
-        self.write_handle
-            .append(Operation::Add(
-                (keyhash, Some(owned_value)),
-                self.version().unwrap_or_default(),
-            ))
-            .publish();
+    ///
+    /// Fails without touching the trie if either the key or the value cannot be serialized.
+    pub fn insert(&mut self, key: K, value: V) -> Result<()> {
+        let op = Operation::Add(Self::encode_entry(key, Some(value))?, self.next_version()?);
+        self.append(op, 1);
+        self.publish();
+
+        Ok(())
     }
 
     /// Add and publish a set of key-value pairs at a specified version.
-    pub fn extend(&mut self, values: Vec<(K, Option<V>)>) {
+    ///
+    /// The whole batch is rejected if any key or value cannot be serialized.
+    pub fn extend(&mut self, values: Vec<(K, Option<V>)>) -> Result<()> {
+        let (op, batch_size) = self.extend_op(values)?;
+        self.append(op, batch_size);
+        self.publish();
+
+        Ok(())
+    }
+
+    /// Add and publish a set of key hashes and encoded values as a single version.
+    pub(crate) fn extend_encoded(
+        &mut self,
+        values: Vec<(KeyHash, Option<OwnedValue>)>,
+    ) -> Result<()> {
+        let batch_size = values.len();
+        let op = Operation::Extend(values, self.next_version()?);
+        self.append(op, batch_size);
+        self.publish();
+
+        Ok(())
+    }
+
+    /// Remove and publish the value stored for a key.
+    pub fn remove(&mut self, key: K) -> Result<()> {
+        let op = Operation::Remove(Self::hash_key(&key)?, self.next_version()?);
+        self.append(op, 1);
+        self.publish();
+
+        Ok(())
+    }
+
+    /// Remove and publish the values stored for a set of keys as a single version.
+    pub fn remove_batch(&mut self, keys: Vec<K>) -> Result<()> {
+        let removals = keys
+            .iter()
+            .map(|key| -> Result<_> { Ok((Self::hash_key(key)?, None)) })
+            .collect::<Result<Vec<_>>>()?;
+        let batch_size = removals.len();
+        let op = Operation::Extend(removals, self.next_version()?);
+        self.append(op, batch_size);
+        self.publish();
+
+        Ok(())
+    }
+
+    /// Append a key-value pair to the operational log without publishing it.
+    ///
+    /// The operation becomes visible to readers on the next `publish`, which may be
+    /// triggered by the configured [`PublishPolicy`].
+    pub fn insert_deferred(&mut self, key: K, value: V) -> Result<()> {
+        let op = Operation::Add(Self::encode_entry(key, Some(value))?, self.next_version()?);
+        self.append_deferred(op, 1);
+
+        Ok(())
+    }
+
+    /// Append a set of key-value pairs to the operational log without publishing them.
+    ///
+    /// The operation becomes visible to readers on the next `publish`, which may be
+    /// triggered by the configured [`PublishPolicy`].
+    pub fn extend_deferred(&mut self, values: Vec<(K, Option<V>)>) -> Result<()> {
+        let (op, batch_size) = self.extend_op(values)?;
+        self.append_deferred(op, batch_size);
+
+        Ok(())
+    }
+
+    /// The version the next appended operation builds on.
+    ///
+    /// Every pending operation will produce its own version once absorbed,
+    /// so they have to be accounted for on top of the published version.
+    pub(crate) fn next_version(&self) -> Result<Version> {
+        let base = self
+            .version()?
+            .checked_add(self.pending_ops as Version)
+            .filter(|base| *base < self.max_version)
+            .ok_or(LeftRightTrieError::VersionLimitReached(self.max_version))?;
+
+        Ok(base)
+    }
+
+    /// Returns the value staged for `key` by an appended but unpublished operation.
+    pub(crate) fn staged(&self, key: &KeyHash) -> Option<&Option<OwnedValue>> {
+        self.staged.get(key)
+    }
+
+    fn append(&mut self, op: Operation, batch_size: usize) {
+        self.write_handle.append(op);
+        self.pending_ops += 1;
+        self.metrics.record_write(batch_size);
+    }
+
+    /// Append `op` and publish it if the [`PublishPolicy`] says so. Otherwise its writes
+    /// are staged so that a [`WriterView`] can read them before they are published.
+    fn append_deferred(&mut self, op: Operation, batch_size: usize) {
+        let due = match self.publish_policy {
+            PublishPolicy::Manual => false,
+            PublishPolicy::EveryNOps(n) => self.pending_ops + 1 >= n,
+            PublishPolicy::Interval(interval) => self.last_publish.elapsed() >= interval,
+        };
+        if due {
+            self.append(op, batch_size);
+            self.publish();
+            return;
+        }
+
+        match &op {
+            Operation::Add((key, value), _) => {
+                self.staged.insert(*key, value.clone());
+            }
+            Operation::Remove(key, _) => {
+                self.staged.insert(*key, None);
+            }
+            Operation::Extend(kvs, _) => self.staged.extend(kvs.iter().cloned()),
+        }
+        self.append(op, batch_size);
+    }
+
+    fn extend_op(&self, values: Vec<(K, Option<V>)>) -> Result<(Operation, usize)> {
         let mapped = values
             .into_iter()
-            .map(|(key, value)| {
-                //TODO: revisit the serializer used to store things on the trie
-                let key = KeyHash::with::<Sha256>(bincode::serialize(&key).unwrap_or_default());
-                let value = if let Some(val) = value {
-                    Some(bincode::serialize(&val).unwrap_or_default())
-                } else {
-                    None
-                };
-
-                (key, value)
-            })
-            .collect();
+            .map(|(key, value)| Self::encode_entry(key, value))
+            .collect::<Result<Vec<_>>>()?;
+        let batch_size = mapped.len();
 
-        self.write_handle
-            .append(Operation::Extend(
-                mapped,
-                self.version().unwrap_or_default(),
-            ))
-            .publish();
+        Ok((Operation::Extend(mapped, self.next_version()?), batch_size))
+    }
+
+    pub(crate) fn hash_key(key: &K) -> Result<KeyHash> {
+        //TODO: revisit the serializer used to store things on the trie
+        Ok(KeyHash::with::<H>(C::encode_key(key)?))
+    }
+
+    pub(crate) fn encode_entry(key: K, value: Option<V>) -> Result<(KeyHash, Option<OwnedValue>)> {
+        let key = Self::hash_key(&key)?;
+        let value = value.map(|val| C::encode(&val)).transpose()?;
+
+        Ok((key, value))
     }
 }
 
-impl<'a, D, K, V, H> PartialEq for LeftRightTrie<'a, K, V, D, H>
+impl<'a, D, K, V, H, C> LeftRightTrie<'a, K, V, D, H, C>
+where
+    D: TreeReader + TreeWriter + VersionedDatabase,
+    H: SimpleHasher,
+    K: Serialize + DeserializeOwned + Clone,
+    V: Serialize + DeserializeOwned + Clone,
+    C: Codec,
+{
+    /// Like `handle`, but with the trie's key and value types fixed on the returned handle.
+    pub fn typed_handle(&self) -> TypedTrieHandle<K, V, D, H, C> {
+        TypedTrieHandle::new(self.handle())
+    }
+}
+
+impl<'a, D, K, V, H, C> LeftRightTrie<'a, K, V, D, H, C>
+where
+    D: TreeReader + TreeWriter + VersionedDatabase,
+    H: SimpleHasher,
+    K: Serialize + Deserialize<'a>,
+    V: Serialize + DeserializeOwned,
+    C: Codec,
+{
+    /// Start a [`SpeculativeStore`] on top of the latest published version of the trie.
+    pub fn speculate(&self) -> SpeculativeStore<K, V, D, H, C> {
+        SpeculativeStore::new(self.handle().inner())
+    }
+
+    /// Apply and publish the writes staged in a [`SpeculativeStore`] as a single new version.
+    ///
+    /// Fails if the trie has moved past the version the store was started from, since the
+    /// root hash computed by the store would no longer match the one produced here.
+    pub fn merge(&mut self, store: SpeculativeStore<K, V, D, H, C>) -> Result<()> {
+        let version = self.next_version()?;
+        if version != store.base_version() {
+            return Err(LeftRightTrieError::VersionConflict {
+                expected: store.base_version(),
+                actual: version,
+            });
+        }
+
+        let value_set = store.value_set();
+        let batch_size = value_set.len();
+        self.append(Operation::Extend(value_set, version), batch_size);
+        self.publish();
+
+        Ok(())
+    }
+}
+
+impl<'a, D, K, V, H, C> LeftRightTrie<'a, K, V, D, H, C>
+where
+    D: TreeReader + TreeWriter + VersionedDatabase,
+    H: NamedHasher,
+    K: Serialize + Deserialize<'a>,
+    V: Serialize + Deserialize<'a>,
+    C: Codec,
+{
+    /// Get a serializable [`ProofBundle`] for `key` at a specified `Version`.
+    pub fn get_proof_bundle(&self, key: &K, version: Version) -> Result<ProofBundle> {
+        self.try_handle()?.get_proof_bundle(key, version)
+    }
+}
+
+impl<'a, D, K, V, H, C> PartialEq for LeftRightTrie<'a, K, V, D, H, C>
 where
     D: TreeReader + TreeWriter + VersionedDatabase,
     H: SimpleHasher,
     K: Serialize + Deserialize<'a>,
     V: Serialize + Deserialize<'a>,
+    C: Codec,
 {
     fn eq(&self, other: &Self) -> bool {
         self.root_latest() == other.root_latest()
     }
 }
 
-impl<'a, D, K, V, H> From<Arc<D>> for LeftRightTrie<'a, K, V, D, H>
+impl<'a, D, K, V, H, C> From<Arc<D>> for LeftRightTrie<'a, K, V, D, H, C>
 where
     D: TreeReader + TreeWriter + VersionedDatabase,
     H: SimpleHasher,
     K: Serialize + Deserialize<'a>,
     V: Serialize + Deserialize<'a>,
+    C: Codec,
 {
     fn from(db: Arc<D>) -> Self {
         let (write_handle, read_handle) = left_right::new_from_empty(JellyfishMerkleTree::new(db));
@@ -200,17 +589,24 @@ where
         Self {
             read_handle,
             write_handle,
+            metrics: Arc::new(NoopMetrics),
+            publish_policy: PublishPolicy::default(),
+            pending_ops: 0,
+            staged: HashMap::new(),
+            last_publish: Instant::now(),
+            max_version: Version::MAX,
             _marker: PhantomData,
         }
     }
 }
 
-impl<'a, D, K, V, H> From<JellyfishMerkleTree<D, H>> for LeftRightTrie<'a, K, V, D, H>
+impl<'a, D, K, V, H, C> From<JellyfishMerkleTree<D, H>> for LeftRightTrie<'a, K, V, D, H, C>
 where
     D: TreeReader + TreeWriter + VersionedDatabase,
     H: SimpleHasher,
     K: Serialize + Deserialize<'a>,
     V: Serialize + Deserialize<'a>,
+    C: Codec,
 {
     fn from(other: JellyfishMerkleTree<D, H>) -> Self {
         let (write_handle, read_handle) = left_right::new_from_empty(other);
@@ -218,30 +614,42 @@ where
         Self {
             read_handle,
             write_handle,
+            metrics: Arc::new(NoopMetrics),
+            publish_policy: PublishPolicy::default(),
+            pending_ops: 0,
+            staged: HashMap::new(),
+            last_publish: Instant::now(),
+            max_version: Version::MAX,
             _marker: PhantomData,
         }
     }
 }
 
-impl<'a, D, K, V, H> Clone for LeftRightTrie<'a, K, V, D, H>
+impl<'a, D, K, V, H, C> Clone for LeftRightTrie<'a, K, V, D, H, C>
 where
     D: TreeReader + TreeWriter + VersionedDatabase,
     H: SimpleHasher,
     K: Serialize + Deserialize<'a>,
     V: Serialize + Deserialize<'a>,
+    C: Codec,
 {
     fn clone(&self) -> Self {
         let inner = self.handle().inner();
-        LeftRightTrie::from(inner)
+        let mut trie = LeftRightTrie::from(inner);
+        trie.set_metrics(self.metrics.clone());
+        trie.set_publish_policy(self.publish_policy);
+        trie.set_max_version(self.max_version);
+        trie
     }
 }
 
-impl<'a, D, K, V, H> Display for LeftRightTrie<'a, K, V, D, H>
+impl<'a, D, K, V, H, C> Display for LeftRightTrie<'a, K, V, D, H, C>
 where
     D: TreeReader + TreeWriter + VersionedDatabase,
     H: SimpleHasher,
     K: Serialize + Deserialize<'a>,
     V: Serialize + Deserialize<'a>,
+    C: Codec,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.handle())
@@ -250,8 +658,12 @@ where
 
 #[cfg(test)]
 mod tests {
-    use patriecia::{JellyfishMerkleIterator, MockTreeStore, VersionedTrie};
-    use std::thread;
+    use patriecia::{JellyfishMerkleIterator, MockTreeStore, Sha256, VersionedTrie};
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        thread,
+        time::Duration,
+    };
 
     use super::*;
 
@@ -265,13 +677,34 @@ mod tests {
         let db = Arc::new(MockTreeStore::new(true));
         let mut trie = LeftRightTrie::<_, _, _, Sha256>::new(db);
 
-        trie.insert("abcdefg", CustomValue { data: 100 });
+        trie.insert("abcdefg", CustomValue { data: 100 }).unwrap();
 
         let value: CustomValue = trie.handle().get(&String::from("abcdefg"), 1).unwrap();
 
         assert_eq!(value, CustomValue { data: 100 });
     }
 
+    #[cfg(feature = "rmp")]
+    #[test]
+    fn should_insert_get_and_prove_with_rmp_codec() {
+        let db = Arc::new(MockTreeStore::new(true));
+        let mut trie = LeftRightTrie::<_, _, _, Sha256, crate::RmpCodec>::new(db);
+
+        trie.insert("abcdefg".to_string(), CustomValue { data: 100 })
+            .unwrap();
+
+        let value: CustomValue = trie.handle().get(&String::from("abcdefg"), 1).unwrap();
+        assert_eq!(value, CustomValue { data: 100 });
+
+        let bundle = trie.get_proof_bundle(&"abcdefg".to_string(), 1).unwrap();
+        crate::verify::verify_entry::<Sha256, crate::RmpCodec, _, _>(&bundle, "abcdefg", &value)
+            .unwrap();
+        assert!(crate::verify::verify_entry::<Sha256, BincodeCodec, _, _>(
+            &bundle, "abcdefg", &value
+        )
+        .is_err());
+    }
+
     #[test]
     fn should_be_read_concurrently() {
         let db = Arc::new(MockTreeStore::new(true));
@@ -284,7 +717,7 @@ mod tests {
         for n in 0..total {
             let key = format!("test-{n}");
 
-            trie.insert(key, CustomValue { data: 12345 });
+            trie.insert(key, CustomValue { data: 12345 }).unwrap();
         }
 
         trie.publish();
@@ -323,4 +756,443 @@ mod tests {
         }
         assert_eq!(count, 18);
     }
+
+    #[test]
+    fn extend_should_produce_a_single_version() {
+        let db = Arc::new(MockTreeStore::new(true));
+        let mut trie = LeftRightTrie::<_, _, _, Sha256>::new(db);
+
+        trie.extend(
+            (0..10)
+                .map(|n| (format!("test-{n}"), Some(CustomValue { data: n })))
+                .collect(),
+        )
+        .unwrap();
+
+        assert_eq!(trie.version(), Ok(1));
+        assert_eq!(trie.len(), Ok(10));
+        for n in 0..10 {
+            let value: CustomValue = trie.handle().get(&format!("test-{n}"), 1).unwrap();
+            assert_eq!(value, CustomValue { data: n });
+        }
+    }
+
+    #[test]
+    fn deferred_inserts_should_publish_according_to_policy() {
+        let db = Arc::new(MockTreeStore::new(true));
+        let mut trie = LeftRightTrie::<_, _, _, Sha256>::new(db);
+        trie.set_publish_policy(PublishPolicy::EveryNOps(3));
+
+        trie.insert_deferred("a", CustomValue { data: 1 }).unwrap();
+        trie.insert_deferred("b", CustomValue { data: 2 }).unwrap();
+        assert_eq!(trie.pending_operations(), 2);
+        assert_eq!(trie.version(), Ok(0));
+
+        trie.insert_deferred("c", CustomValue { data: 3 }).unwrap();
+        assert_eq!(trie.pending_operations(), 0);
+        assert_eq!(trie.version(), Ok(3));
+
+        let value: CustomValue = trie.handle().get(&String::from("b"), 3).unwrap();
+        assert_eq!(value, CustomValue { data: 2 });
+    }
+
+    #[test]
+    fn should_refuse_writes_past_max_version() {
+        let db = Arc::new(MockTreeStore::new(true));
+        let mut trie = LeftRightTrie::<_, _, _, Sha256>::new(db);
+        trie.set_max_version(2);
+
+        trie.insert("a", CustomValue { data: 1 }).unwrap();
+        trie.insert("b", CustomValue { data: 2 }).unwrap();
+        assert_eq!(
+            trie.insert("c", CustomValue { data: 3 }),
+            Err(LeftRightTrieError::VersionLimitReached(2))
+        );
+        assert_eq!(trie.version(), Ok(2));
+    }
+
+    #[test]
+    fn should_remove_values_through_left_right() {
+        let db = Arc::new(MockTreeStore::new(true));
+        let mut trie = LeftRightTrie::<_, _, _, Sha256>::new(db);
+
+        trie.extend(vec![
+            ("a", Some(CustomValue { data: 1 })),
+            ("b", Some(CustomValue { data: 2 })),
+            ("c", Some(CustomValue { data: 3 })),
+        ])
+        .unwrap();
+
+        trie.remove("a").unwrap();
+        assert!(!trie.handle().contains(&"a", 2).unwrap());
+        assert_eq!(trie.len(), Ok(2));
+
+        trie.remove_batch(vec!["b", "c"]).unwrap();
+        assert_eq!(trie.version(), Ok(3));
+        assert_eq!(trie.is_empty(), Ok(true));
+    }
+
+    #[test]
+    fn should_rebuild_state_from_exported_chunks() {
+        let mut source = LeftRightTrie::<_, _, _, Sha256>::new(Arc::new(MockTreeStore::new(true)));
+        source
+            .extend(
+                (0..10)
+                    .map(|n| (format!("test-{n}"), Some(CustomValue { data: n })))
+                    .collect(),
+            )
+            .unwrap();
+
+        let chunks = source
+            .export_chunks(1, 3)
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(chunks.len(), 4);
+
+        // Ship the chunks the way a peer would receive them.
+        let chunks = chunks
+            .iter()
+            .map(|chunk| borsh::from_slice::<StateChunk>(&borsh::to_vec(chunk).unwrap()).unwrap())
+            .collect::<Vec<_>>();
+
+        let mut target = LeftRightTrie::<String, CustomValue, _, Sha256>::new(Arc::new(
+            MockTreeStore::new(true),
+        ));
+        let root = target.import_chunks(chunks).unwrap();
+
+        assert_eq!(root, source.root_latest().unwrap());
+        assert_eq!(target.len(), Ok(10));
+    }
+
+    #[test]
+    fn should_export_a_single_chunk_for_an_unbounded_chunk_size() {
+        let mut source = LeftRightTrie::<_, _, _, Sha256>::new(Arc::new(MockTreeStore::new(true)));
+        source
+            .extend(
+                (0..10)
+                    .map(|n| (format!("test-{n}"), Some(CustomValue { data: n })))
+                    .collect(),
+            )
+            .unwrap();
+
+        let chunks = source
+            .export_chunks(1, usize::MAX)
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].entries.len(), 10);
+    }
+
+    #[test]
+    fn should_not_publish_chunks_that_fail_verification() {
+        let mut source = LeftRightTrie::<_, _, _, Sha256>::new(Arc::new(MockTreeStore::new(true)));
+        source
+            .extend(
+                (0..10)
+                    .map(|n| (format!("test-{n}"), Some(CustomValue { data: n })))
+                    .collect(),
+            )
+            .unwrap();
+        let chunks = source
+            .export_chunks(1, 3)
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+
+        let mut target = LeftRightTrie::<String, CustomValue, _, Sha256>::new(Arc::new(
+            MockTreeStore::new(true),
+        ));
+
+        let mut tampered = chunks.clone();
+        tampered[1].entries[0].1 = BincodeCodec::encode(&CustomValue { data: 100 }).unwrap();
+        assert!(matches!(
+            target.import_chunks(tampered),
+            Err(LeftRightTrieError::RootMismatch { .. })
+        ));
+
+        let mut mixed = chunks.clone();
+        mixed[2].version = 2;
+        assert!(matches!(
+            target.import_chunks(mixed),
+            Err(LeftRightTrieError::InvalidChunks(_))
+        ));
+
+        assert!(matches!(
+            target.import_chunks(chunks[1..].to_vec()),
+            Err(LeftRightTrieError::InvalidChunks(_))
+        ));
+        assert!(matches!(
+            target.import_chunks(vec![]),
+            Err(LeftRightTrieError::InvalidChunks(_))
+        ));
+
+        assert_eq!(target.version(), Ok(0));
+        assert_eq!(target.pending_operations(), 0);
+        assert_eq!(target.import_chunks(chunks), source.root_latest());
+    }
+
+    #[test]
+    fn roots_range_should_return_the_root_of_each_version() {
+        let db = Arc::new(MockTreeStore::new(true));
+        let mut trie = LeftRightTrie::<String, CustomValue, _, Sha256>::new(db);
+        for data in 0..3 {
+            trie.insert(format!("key-{data}"), CustomValue { data })
+                .unwrap();
+        }
+
+        let roots = trie.roots_range(1, 3).unwrap();
+        assert_eq!(
+            roots
+                .iter()
+                .map(|(version, _)| *version)
+                .collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        assert_eq!(roots[2].1, trie.root_latest().unwrap());
+        assert_ne!(roots[0].1, roots[1].1);
+
+        let from_zero = trie.roots_range(0, 3).unwrap();
+        assert_eq!(from_zero[0], (0, EMPTY_ROOT_HASH));
+        assert_eq!(from_zero[1..], roots[..]);
+        assert!(matches!(
+            trie.roots_range(1, 4),
+            Err(LeftRightTrieError::VersionTooNew { .. })
+        ));
+    }
+
+    #[test]
+    fn typed_handle_should_read_without_type_annotations() {
+        let db = Arc::new(MockTreeStore::new(true));
+        let mut trie = LeftRightTrie::<String, CustomValue, _, Sha256>::new(db);
+
+        trie.insert("abcdefg".to_string(), CustomValue { data: 100 })
+            .unwrap();
+
+        let handle = trie.typed_handle();
+        assert_eq!(
+            handle.get(&"abcdefg".to_string(), 1),
+            Ok(CustomValue { data: 100 })
+        );
+        assert!(handle.contains(&"abcdefg".to_string(), 1).unwrap());
+        assert_eq!(handle.len(), 1);
+        assert_eq!(handle.get_opt(&"hijklmn".to_string(), 1), Ok(None));
+        assert_eq!(
+            handle.batch_get(&["abcdefg".to_string(), "hijklmn".to_string()], 1),
+            Ok(vec![Some(CustomValue { data: 100 }), None])
+        );
+        assert_eq!(
+            handle.batch_contains(&["hijklmn".to_string(), "abcdefg".to_string()], 1),
+            Ok(vec![false, true])
+        );
+    }
+
+    #[test]
+    fn speculative_writes_should_not_be_visible_until_merged() {
+        let db = Arc::new(MockTreeStore::new(true));
+        let mut trie = LeftRightTrie::<String, CustomValue, _, Sha256>::new(db);
+        trie.insert("a".to_string(), CustomValue { data: 1 })
+            .unwrap();
+
+        let mut speculative = trie.speculate();
+        speculative
+            .insert("b".to_string(), CustomValue { data: 2 })
+            .unwrap();
+        speculative.remove("a".to_string()).unwrap();
+
+        assert_eq!(
+            speculative.get(&"b".to_string()),
+            Ok(Some(CustomValue { data: 2 }))
+        );
+        assert_eq!(speculative.get(&"a".to_string()), Ok(None));
+        assert!(!trie.handle().contains(&"b".to_string(), 1).unwrap());
+
+        let expected_root = speculative.root_hash().unwrap();
+        assert_ne!(expected_root, trie.root_latest().unwrap());
+
+        trie.merge(speculative).unwrap();
+        assert_eq!(trie.version(), Ok(2));
+        assert_eq!(trie.root_latest(), Ok(expected_root));
+    }
+
+    #[test]
+    fn empty_tries_should_report_the_empty_root() {
+        let db = Arc::new(MockTreeStore::new(true));
+        let mut trie = LeftRightTrie::<String, CustomValue, _, Sha256>::new(db);
+        assert_eq!(trie.root_latest(), Ok(EMPTY_ROOT_HASH));
+
+        trie.insert("a".to_string(), CustomValue { data: 1 })
+            .unwrap();
+        assert_ne!(trie.root_latest(), Ok(EMPTY_ROOT_HASH));
+
+        trie.remove("a".to_string()).unwrap();
+        assert_eq!(trie.root_latest(), Ok(EMPTY_ROOT_HASH));
+        assert_eq!(trie.root(0), Ok(EMPTY_ROOT_HASH));
+    }
+
+    #[test]
+    fn should_speculate_on_a_fresh_trie() {
+        let db = Arc::new(MockTreeStore::new(true));
+        let mut trie = LeftRightTrie::<String, CustomValue, _, Sha256>::new(db);
+
+        let mut speculative = trie.speculate();
+        assert_eq!(speculative.get(&"a".to_string()), Ok(None));
+        assert_eq!(speculative.root_hash(), Ok(EMPTY_ROOT_HASH));
+
+        speculative
+            .insert("a".to_string(), CustomValue { data: 1 })
+            .unwrap();
+        assert_eq!(
+            speculative.get(&"a".to_string()),
+            Ok(Some(CustomValue { data: 1 }))
+        );
+        let expected_root = speculative.root_hash().unwrap();
+
+        trie.merge(speculative).unwrap();
+        assert_eq!(trie.root_latest(), Ok(expected_root));
+    }
+
+    #[test]
+    fn should_return_typed_errors_for_stale_merges_and_empty_chunks() {
+        let db = Arc::new(MockTreeStore::new(true));
+        let mut trie = LeftRightTrie::<String, CustomValue, _, Sha256>::new(db);
+        trie.insert("a".to_string(), CustomValue { data: 1 })
+            .unwrap();
+
+        let speculative = trie.speculate();
+        trie.insert("b".to_string(), CustomValue { data: 2 })
+            .unwrap();
+        assert_eq!(
+            trie.merge(speculative),
+            Err(LeftRightTrieError::VersionConflict {
+                expected: 1,
+                actual: 2
+            })
+        );
+        assert!(matches!(
+            trie.export_chunks(2, 0),
+            Err(LeftRightTrieError::InvalidChunkSize)
+        ));
+    }
+
+    #[test]
+    fn writer_view_should_read_pending_operations() {
+        let db = Arc::new(MockTreeStore::new(true));
+        let mut trie = LeftRightTrie::<String, CustomValue, _, Sha256>::new(db);
+        trie.insert("a".to_string(), CustomValue { data: 1 })
+            .unwrap();
+        assert!(trie.staged.is_empty());
+
+        trie.insert_deferred("b".to_string(), CustomValue { data: 2 })
+            .unwrap();
+        trie.insert_deferred("c".to_string(), CustomValue { data: 3 })
+            .unwrap();
+
+        let view = trie.writer_view();
+        assert_eq!(
+            view.get(&"a".to_string()),
+            Ok(Some(CustomValue { data: 1 }))
+        );
+        assert_eq!(
+            view.get(&"c".to_string()),
+            Ok(Some(CustomValue { data: 3 }))
+        );
+        assert!(!view.contains(&"d".to_string()).unwrap());
+        assert!(!trie.handle().contains(&"c".to_string(), 1).unwrap());
+
+        assert_eq!(trie.staged.len(), 2);
+
+        trie.publish();
+        assert!(trie.staged.is_empty());
+        assert!(trie.handle().contains(&"c".to_string(), 3).unwrap());
+    }
+
+    #[test]
+    fn deferred_operations_published_by_the_policy_should_not_be_staged() {
+        let db = Arc::new(MockTreeStore::new(true));
+        let mut trie = LeftRightTrie::<String, CustomValue, _, Sha256>::new(db);
+        trie.set_publish_policy(PublishPolicy::EveryNOps(2));
+
+        trie.insert_deferred("a".to_string(), CustomValue { data: 1 })
+            .unwrap();
+        assert_eq!(trie.staged.len(), 1);
+
+        trie.extend_deferred(vec![("b".to_string(), Some(CustomValue { data: 2 }))])
+            .unwrap();
+        assert!(trie.staged.is_empty());
+        assert_eq!(trie.pending_operations(), 0);
+        assert!(trie.handle().contains(&"b".to_string(), 2).unwrap());
+    }
+
+    #[derive(Debug, Default)]
+    struct CountingMetrics {
+        reads: AtomicUsize,
+        writes: AtomicUsize,
+        publishes: AtomicUsize,
+    }
+
+    impl StorageMetrics for CountingMetrics {
+        fn record_read(&self) {
+            self.reads.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn record_write(&self, batch_size: usize) {
+            self.writes.fetch_add(batch_size, Ordering::SeqCst);
+        }
+
+        fn record_publish(&self, _elapsed: Duration) {
+            self.publishes.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn should_report_writes_and_publishes_to_metrics() {
+        let db = Arc::new(MockTreeStore::new(true));
+        let mut trie = LeftRightTrie::<_, _, _, Sha256>::new(db);
+        let metrics = Arc::new(CountingMetrics::default());
+        trie.set_metrics(metrics.clone());
+
+        trie.insert("abcdefg", CustomValue { data: 100 }).unwrap();
+        trie.extend(vec![
+            ("hijklmn", Some(CustomValue { data: 200 })),
+            ("opqrstu", Some(CustomValue { data: 300 })),
+        ])
+        .unwrap();
+
+        assert_eq!(metrics.writes.load(Ordering::SeqCst), 3);
+        assert_eq!(metrics.publishes.load(Ordering::SeqCst), 2);
+        assert_eq!(metrics.reads.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn should_report_reads_to_metrics() {
+        let db = Arc::new(MockTreeStore::new(true));
+        let mut trie = LeftRightTrie::<String, CustomValue, _, Sha256>::new(db);
+        let metrics = Arc::new(CountingMetrics::default());
+        trie.set_metrics(metrics.clone());
+
+        trie.insert("a".to_string(), CustomValue { data: 1 })
+            .unwrap();
+        let _ = trie.len().unwrap();
+        let _ = trie.clone();
+        assert_eq!(metrics.reads.load(Ordering::SeqCst), 0);
+
+        let handle = trie.handle();
+        handle
+            .get::<String, CustomValue>(&"a".to_string(), 1)
+            .unwrap();
+        assert!(!handle.contains(&"b".to_string(), 1).unwrap());
+        trie.root_latest().unwrap();
+        assert_eq!(metrics.reads.load(Ordering::SeqCst), 3);
+
+        let factory = trie.factory();
+        let reader = factory.handle();
+        let tree = reader.enter().map(|tree| tree.clone()).unwrap();
+        JellyfishMerkleTreeWrapper::<_, Sha256>::new(tree)
+            .with_metrics(trie.metrics())
+            .get_opt::<String, CustomValue>(&"a".to_string(), 1)
+            .unwrap();
+        assert_eq!(metrics.reads.load(Ordering::SeqCst), 4);
+    }
 }
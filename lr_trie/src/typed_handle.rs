@@ -0,0 +1,134 @@
+use std::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+
+use patriecia::{
+    KeyHash, RootHash, SimpleHasher, TreeReader, TreeWriter, Version, VersionedDatabase,
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{BincodeCodec, Codec, JellyfishMerkleTreeWrapper, PinnedView, Result};
+
+/// A [`JellyfishMerkleTreeWrapper`] with its key and value types fixed, so reads
+/// don't have to re-specify them on every call.
+pub struct TypedTrieHandle<K, V, D, H, C = BincodeCodec>
+where
+    D: TreeReader + TreeWriter + VersionedDatabase,
+    H: SimpleHasher,
+    C: Codec,
+{
+    inner: JellyfishMerkleTreeWrapper<D, H, C>,
+    _marker: PhantomData<(K, V)>,
+}
+
+impl<K, V, D, H, C> TypedTrieHandle<K, V, D, H, C>
+where
+    D: TreeReader + TreeWriter + VersionedDatabase,
+    H: SimpleHasher,
+    K: Serialize + DeserializeOwned + Clone,
+    V: Serialize + DeserializeOwned + Clone,
+    C: Codec,
+{
+    pub fn new(inner: JellyfishMerkleTreeWrapper<D, H, C>) -> Self {
+        Self {
+            inner,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the untyped wrapper this handle reads through.
+    pub fn inner(&self) -> &JellyfishMerkleTreeWrapper<D, H, C> {
+        &self.inner
+    }
+
+    /// Get the value associated with a key at a specified `Version`.
+    pub fn get(&self, key: &K, version: Version) -> Result<V> {
+        self.inner.get(key, version)
+    }
+
+    /// Get the value associated with a key at a specified `Version`, or `None` if the
+    /// key has no value at that version.
+    pub fn get_opt(&self, key: &K, version: Version) -> Result<Option<V>> {
+        self.inner.get_opt(key, version)
+    }
+
+    /// Get the values for several keys at a specified `Version`, in the order given.
+    pub fn batch_get(&self, keys: &[K], version: Version) -> Result<Vec<Option<V>>> {
+        keys.iter().map(|key| self.get_opt(key, version)).collect()
+    }
+
+    /// Returns true if the tree contains the specified key at `Version`.
+    pub fn contains(&self, key: &K, version: Version) -> Result<bool> {
+        self.inner.contains(key, version)
+    }
+
+    /// Returns whether the tree contains each of `keys` at `Version`, in the order given.
+    pub fn batch_contains(&self, keys: &[K], version: Version) -> Result<Vec<bool>> {
+        keys.iter().map(|key| self.contains(key, version)).collect()
+    }
+
+    /// Get the `RootHash` of the tree at a specified `Version`.
+    pub fn root_hash(&self, version: Version) -> Result<RootHash> {
+        self.inner.root_hash(version)
+    }
+
+    /// Iterate over the key hashes stored at `version` without decoding any values.
+    pub fn keys(&self, version: Version) -> Result<impl Iterator<Item = Result<KeyHash>>> {
+        self.inner.keys(version)
+    }
+
+    /// Iterate over the values stored at `version`, in key hash order.
+    pub fn values(&self, version: Version) -> Result<impl Iterator<Item = Result<V>>> {
+        self.inner.values(version)
+    }
+
+    /// Pin reads to `version`; see [`JellyfishMerkleTreeWrapper::pin`].
+    pub fn pin(&self, version: Version) -> Result<PinnedView<D, H, C>> {
+        self.inner.pin(version)
+    }
+
+    /// Get the latest `Version` of the tree known to the database.
+    pub fn version(&self) -> Version {
+        self.inner.version()
+    }
+
+    /// Get the number of values stored at the latest version of the tree.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns true if no values are stored at the latest version of the tree.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+}
+
+impl<K, V, D, H, C> Clone for TypedTrieHandle<K, V, D, H, C>
+where
+    D: TreeReader + TreeWriter + VersionedDatabase,
+    H: SimpleHasher,
+    C: Codec,
+    JellyfishMerkleTreeWrapper<D, H, C>: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<K, V, D, H, C> Debug for TypedTrieHandle<K, V, D, H, C>
+where
+    D: TreeReader + TreeWriter + VersionedDatabase,
+    H: SimpleHasher,
+    C: Codec,
+    JellyfishMerkleTreeWrapper<D, H, C>: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypedTrieHandle")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
@@ -0,0 +1,87 @@
+//! Proof verification for light clients.
+//!
+//! Everything here works on a [`ProofBundle`] alone, without a database, a tree or a
+//! left-right handle, so it can be used by callers that only hold proofs received from
+//! a full node.
+
+use patriecia::KeyHash;
+use serde::Serialize;
+
+use crate::{Codec, LeftRightTrieError, NamedHasher, ProofBundle, Result};
+
+/// Computes the key hash a trie using hasher `H` and codec `C` stores `key` under.
+pub fn key_hash<H: NamedHasher, C: Codec, K: Serialize + ?Sized>(key: &K) -> Result<KeyHash> {
+    Ok(KeyHash::with::<H>(C::encode_key(key)?))
+}
+
+/// Checks that `bundle` proves `value` is stored for its key under its root hash.
+///
+/// `value` is the encoded value, as stored in the trie.
+pub fn verify_inclusion<H: NamedHasher>(bundle: &ProofBundle, value: &[u8]) -> Result<()> {
+    bundle
+        .proof::<H>()?
+        .verify(bundle.root_hash(), bundle.key_hash(), Some(value))
+        .map_err(|err| LeftRightTrieError::Proof(err.to_string()))
+}
+
+/// Checks that `bundle` proves no value is stored for its key under its root hash.
+pub fn verify_exclusion<H: NamedHasher>(bundle: &ProofBundle) -> Result<()> {
+    bundle
+        .proof::<H>()?
+        .verify_nonexistence(bundle.root_hash(), bundle.key_hash())
+        .map_err(|err| LeftRightTrieError::Proof(err.to_string()))
+}
+
+/// Checks that `bundle` is a proof for `key` and that it proves `value` is stored for it.
+pub fn verify_entry<H, C, K, V>(bundle: &ProofBundle, key: &K, value: &V) -> Result<()>
+where
+    H: NamedHasher,
+    C: Codec,
+    K: Serialize + ?Sized,
+    V: Serialize + ?Sized,
+{
+    if key_hash::<H, C, K>(key)? != bundle.key_hash() {
+        return Err(LeftRightTrieError::Proof(
+            "proof bundle is for a different key".to_string(),
+        ));
+    }
+
+    verify_inclusion::<H>(bundle, &C::encode(value)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use patriecia::{MockTreeStore, Sha256};
+
+    use super::*;
+    use crate::{BincodeCodec, LeftRightTrie};
+
+    fn trie() -> LeftRightTrie<'static, String, u64, MockTreeStore, Sha256> {
+        let mut trie = LeftRightTrie::new(Arc::new(MockTreeStore::new(true)));
+        trie.extend(vec![("a".to_string(), Some(1)), ("b".to_string(), Some(2))])
+            .unwrap();
+        trie
+    }
+
+    #[test]
+    fn should_verify_inclusion_without_a_tree() {
+        let bundle = trie().get_proof_bundle(&"a".to_string(), 1).unwrap();
+
+        verify_entry::<Sha256, BincodeCodec, _, _>(&bundle, "a", &1u64).unwrap();
+        assert!(verify_entry::<Sha256, BincodeCodec, _, _>(&bundle, "a", &2u64).is_err());
+        assert!(verify_entry::<Sha256, BincodeCodec, _, _>(&bundle, "b", &2u64).is_err());
+        assert!(verify_exclusion::<Sha256>(&bundle).is_err());
+    }
+
+    #[test]
+    fn should_verify_exclusion_without_a_tree() {
+        let bundle = trie().get_proof_bundle(&"c".to_string(), 1).unwrap();
+
+        verify_exclusion::<Sha256>(&bundle).unwrap();
+        assert!(
+            verify_inclusion::<Sha256>(&bundle, &BincodeCodec::encode(&3u64).unwrap()).is_err()
+        );
+    }
+}
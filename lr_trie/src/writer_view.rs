@@ -0,0 +1,63 @@
+use patriecia::{
+    OwnedValue, SimpleHasher, TreeReader, TreeWriter, VersionedDatabase, VersionedTrie,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{Codec, LeftRightTrie, LeftRightTrieError, Result};
+
+/// Read-your-writes view over a [`LeftRightTrie`].
+///
+/// Operations appended with the `*_deferred` methods are not visible to readers until
+/// they are published. A `WriterView` consults those pending operations first and only
+/// falls back to the published data for keys they don't touch.
+#[derive(Debug)]
+pub struct WriterView<'t, 'a, K, V, D, H, C>
+where
+    D: TreeReader + TreeWriter + VersionedDatabase,
+    H: SimpleHasher,
+    K: Serialize + Deserialize<'a>,
+    V: Serialize + Deserialize<'a>,
+    C: Codec,
+{
+    trie: &'t LeftRightTrie<'a, K, V, D, H, C>,
+}
+
+impl<'t, 'a, K, V, D, H, C> WriterView<'t, 'a, K, V, D, H, C>
+where
+    D: TreeReader + TreeWriter + VersionedDatabase,
+    H: SimpleHasher,
+    K: Serialize + Deserialize<'a>,
+    V: Serialize + DeserializeOwned,
+    C: Codec,
+{
+    pub fn new(trie: &'t LeftRightTrie<'a, K, V, D, H, C>) -> Self {
+        Self { trie }
+    }
+
+    /// Get the value for a key, including operations that have not been published yet.
+    pub fn get(&self, key: &K) -> Result<Option<V>> {
+        self.get_raw(key)?
+            .map(|raw_value| C::decode(&raw_value))
+            .transpose()
+    }
+
+    /// Returns true if the key has a value, including operations that have not been published yet.
+    pub fn contains(&self, key: &K) -> Result<bool> {
+        Ok(self.get_raw(key)?.is_some())
+    }
+
+    fn get_raw(&self, key: &K) -> Result<Option<OwnedValue>> {
+        let key = LeftRightTrie::<'a, K, V, D, H, C>::hash_key(key)?;
+        if let Some(staged) = self.trie.staged(&key) {
+            return Ok(staged.clone());
+        }
+
+        let published = self.trie.handle().inner();
+        if published.is_empty() {
+            return Ok(None);
+        }
+        published
+            .get(key, published.version())
+            .map_err(|err| LeftRightTrieError::Io(err.to_string()))
+    }
+}